@@ -0,0 +1,32 @@
+use anyhow::{Context, Error};
+use meh_asus::battery::{Battery, ChargeLimit};
+
+fn main() -> Result<(), Error> {
+    let battery = Battery::find()?;
+
+    if let Some(limit) = std::env::args().nth(1) {
+        let limit = limit
+            .parse()
+            .ok()
+            .and_then(ChargeLimit::new)
+            .context("charge limit should be a percentage between 1 and 100")?;
+        battery.set_charge_limit(limit)?;
+    }
+
+    let health = battery.health()?;
+    println!(
+        "{}% ({:?}), charge limit {}%",
+        battery.capacity()?,
+        battery.status()?,
+        battery.charge_limit()?.percent()
+    );
+    println!(
+        "Health {:.1}%, {} cycles",
+        health.percent(),
+        health
+            .cycle_count
+            .map_or(String::from("unknown"), |count| count.to_string())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use std::process::ExitCode;
+
+use meh_asus::camera::{Camera, CameraState};
+
+fn main() -> ExitCode {
+    let result = Camera::detect().and_then(|camera| camera.toggle());
+
+    match result {
+        Ok(CameraState::Enabled) => {
+            println!("Camera has been enabled");
+            ExitCode::SUCCESS
+        }
+        Ok(CameraState::Disabled) => {
+            println!("Camera has been disabled");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to toggle the camera!\n{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
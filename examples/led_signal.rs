@@ -0,0 +1,16 @@
+use anyhow::Error;
+use meh_asus::common_hardware::camera_led;
+use meh_asus::led_pattern::LedPattern;
+
+fn main() -> Result<(), Error> {
+    let pattern: LedPattern = std::env::args()
+        .skip(1)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .parse()?;
+
+    // wait for the pattern to end, the camera led is restored afterwards
+    pattern.play(camera_led::get())?.join().transpose()?;
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use std::{env, fmt::Debug, process::ExitCode};
+
+use meh_asus::common_hardware::toggles::Toggles;
+
+// Run from a boot-time or resume-time hook to bring back the settings reset by the firmware.
+// Pass `snapshot` to store the current state of the toggles instead, e.g. before a firmware update.
+fn main() -> ExitCode {
+    let snapshot = env::args().nth(1).as_deref() == Some("snapshot");
+    let (toggles, action) = if snapshot {
+        (Toggles::snapshot(), ("Stored", "as"))
+    } else {
+        (Toggles::restore(), ("Restored", "to"))
+    };
+
+    let toggles = match toggles {
+        Ok(toggles) => toggles,
+        Err(e) => {
+            eprintln!("Failed to handle the toggles!\n{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    print(action, "touchpad", toggles.touchpad);
+    print(action, "fn-lock", toggles.fnlock);
+    print(action, "boot sound", toggles.boot_sound);
+    print(action, "MCU powersave", toggles.mcu_powersave);
+    print(action, "lightbar", toggles.lightbar);
+
+    ExitCode::SUCCESS
+}
+
+fn print((verb, preposition): (&str, &str), name: &str, state: Option<impl Debug>) {
+    if let Some(state) = state {
+        println!("{} {} {} {:?}", verb, name, preposition, state);
+    }
+}
@@ -2,59 +2,56 @@ use std::{thread::sleep, time::Duration};
 
 use meh_asus::pwm::fan::AsusNbWmiFanMode;
 use meh_asus::pwm::pwm_enable::error::PwmEnableError;
-use meh_asus::pwm::pwm_enable::traits::{PwmHardware, ReadConfig, WriteConfig};
-use meh_asus::pwm::pwm_enable::{PwmEnable, PwmEnableReadOnly};
-
-macro_rules! print_config {
-    ($($x:expr),+ $(,)?) => {
-        $( println!("{} => {} ({} rpm)", $x.get_label().unwrap(), $x.get_fan_mode().unwrap(), $x.get_input().unwrap()); )+
-        println!();
-    };
+use meh_asus::pwm::pwm_enable::traits::{PwmEnableState, ReadConfig, WriteConfig};
+use meh_asus::pwm::pwm_enable::{DiscoveredFan, PwmEnable, PwmEnableReadOnly};
+
+fn print_config<T: PwmEnableState>(fans: &[DiscoveredFan<T>]) {
+    for DiscoveredFan { label, fan } in fans {
+        println!(
+            "{} => {} ({} rpm)",
+            label,
+            fan.get_fan_mode().unwrap(),
+            fan.get_input().unwrap()
+        );
+    }
+    println!();
 }
 
 fn main() -> Result<(), PwmEnableError> {
-    let fan1: PwmEnable<PwmEnableReadOnly> = PwmEnable::find_and_get(1)?;
-    let fan2: PwmEnable<PwmEnableReadOnly> = PwmEnable::find_and_get(2)?;
-
-    // (_, _)
-    print_config!(fan1, fan2);
-
-    let mut fan1 = fan1.make_writable()?;
-    let mut fan2 = fan2.make_writable()?;
+    let fans: Vec<DiscoveredFan<PwmEnableReadOnly>> = PwmEnable::discover()?;
 
     // (_, _)
-    print_config!(fan1, fan2);
-
-    if let Err(e) = fan1.set_fan_mode(AsusNbWmiFanMode::FullSpeed) {
-        eprintln!("Failed to set fan1 to FullSpeed: {}", e);
-    }
-
-    // (FullSpeed, _)
-    print_config!(fan1, fan2);
-    sleep(Duration::from_secs(5));
-
-    if let Err(e) = fan2.set_fan_mode(AsusNbWmiFanMode::FullSpeed) {
-        eprintln!("Failed to set fan2 to FullSpeed: {}", e);
-    }
-
-    // (FullSpeed, FullSpeed)
-    print_config!(fan1, fan2);
-    sleep(Duration::from_secs(5));
-
-    if let Err(e) = fan1.set_fan_mode(AsusNbWmiFanMode::Auto) {
-        eprintln!("Failed to set fan1 to Auto: {}", e);
+    print_config(&fans);
+
+    let mut fans = fans
+        .into_iter()
+        .map(|DiscoveredFan { label, fan }| {
+            fan.make_writable()
+                .map(|fan| DiscoveredFan { label, fan })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // switch each fan to FullSpeed, one after the other
+    for i in 0..fans.len() {
+        if let Err(e) = fans[i].fan.set_fan_mode(AsusNbWmiFanMode::FullSpeed) {
+            eprintln!("Failed to set {} to FullSpeed: {}", fans[i].label, e);
+        }
+
+        print_config(&fans);
+        sleep(Duration::from_secs(5));
     }
 
-    // (Auto, FullSpeed)
-    print_config!(fan1, fan2);
-    sleep(Duration::from_secs(5));
+    // and back to Auto
+    for i in 0..fans.len() {
+        if let Err(e) = fans[i].fan.set_fan_mode(AsusNbWmiFanMode::Auto) {
+            eprintln!("Failed to set {} to Auto: {}", fans[i].label, e);
+        }
 
-    if let Err(e) = fan2.set_fan_mode(AsusNbWmiFanMode::Auto) {
-        eprintln!("Failed to set fan2 to Auto: {}", e);
+        print_config(&fans);
+        if i + 1 < fans.len() {
+            sleep(Duration::from_secs(5));
+        }
     }
 
-    // (Auto, Auto)
-    print_config!(fan1, fan2);
-
     Ok(())
 }
@@ -0,0 +1,24 @@
+use std::process::ExitCode;
+
+use meh_asus::common_hardware::thermal_policy::ThermalPolicy;
+
+fn main() -> ExitCode {
+    let policy = match ThermalPolicy::detect() {
+        Ok(policy) => policy,
+        Err(e) => {
+            eprintln!("Failed to detect thermal policy!\n{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match policy.cycle() {
+        Ok(profile) => {
+            println!("Switched thermal profile to {:?}", profile);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to switch thermal profile!\n{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
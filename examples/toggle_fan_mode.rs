@@ -2,7 +2,7 @@ use anyhow::{Context, Error};
 use meh_asus::pwm::fan::AsusNbWmiFanMode;
 use meh_asus::pwm::pwm_enable::error::{FanModeReadError, InputReadError, LabelReadError};
 use meh_asus::pwm::pwm_enable::traits::{PwmEnableState, PwmHardware, ReadConfig, WriteConfig};
-use meh_asus::pwm::pwm_enable::{PwmEnable, PwmEnableReadOnly, PwmEnableReadWrite};
+use meh_asus::pwm::pwm_enable::{DiscoveredFan, PwmEnable, PwmEnableReadOnly, PwmEnableReadWrite};
 use thiserror::Error;
 
 #[derive(Debug)]
@@ -53,46 +53,37 @@ where
 }
 
 fn main() -> Result<(), Error> {
-    let fans: Result<(PwmEnable<PwmEnableReadWrite>, PwmEnable<PwmEnableReadWrite>), ()> = {
-        let fan1: PwmEnable<PwmEnableReadWrite> = PwmEnable::find_and_get(1)
-            .context("fan1 hardware not found or failed to open config")?;
-        let fan2: PwmEnable<PwmEnableReadWrite> = PwmEnable::find_and_get(2)
-            .context("fan2 hardware not found or failed to open config")?;
+    match PwmEnable::<PwmEnableReadWrite>::discover() {
+        Ok(mut fans) => {
+            let first = fans.first().context("no fan found")?;
+            let config = get_config(&first.fan).context("Error reading fan config")?;
 
-        Ok((fan1, fan2))
-    };
+            // lets sync all fans to same mode
+            let next_mode = get_next_mode(&config);
 
-    if let Ok((mut fan1, mut fan2)) = fans {
-        let fan1_config = get_config(&fan1).expect("Error reading fan1 config");
+            for DiscoveredFan { label, fan } in &mut fans {
+                fan.set_fan_mode(next_mode)
+                    .with_context(|| format!("Failed to switch {label} to next fan mode"))?;
+            }
 
-        // lets sync both fans to same mode
-        let next_mode = get_next_mode(&fan1_config);
+            println!("Switched fan mode to next settings");
+            for DiscoveredFan { label, fan } in &fans {
+                let config = get_config(fan)
+                    .with_context(|| format!("Error reading {label} config"))?;
+                println!("{}", config);
+            }
+        }
+        Err(_) => {
+            let fans = PwmEnable::<PwmEnableReadOnly>::discover()
+                .context("fan hardware not found or failed to read config")?;
 
-        let fan1_config = get_config(&fan1).expect("Error reading fan1 config");
-        let fan2_config = get_config(&fan2).expect("Error reading fan1 config");
-
-        fan1.set_fan_mode(next_mode)
-            .context("Failed to switch to next fan mode")?;
-        fan2.set_fan_mode(next_mode)
-            .context("Failed to switch to next fan mode")?;
-
-        println!(
-            "Switched fan mode to next settings\n{}\n{}",
-            fan1_config, fan2_config
-        );
-    } else {
-        let fan1: PwmEnable<PwmEnableReadOnly> = PwmEnable::find_and_get(1)
-            .context("fan1 hardware not found or failed to read config")?;
-        let fan2: PwmEnable<PwmEnableReadOnly> = PwmEnable::find_and_get(2)
-            .context("fan2 hardware not found or failed to read config")?;
-
-        let fan1_config = get_config(&fan1).context("Error reading fan1 config")?;
-        let fan2_config = get_config(&fan2).context("Error reading fan2 config")?;
-
-        eprintln!(
-            "{}\n{}\nFailed to open fan hardware as read-write.\nPlease run this program as root to control the fans.",
-            fan1_config, fan2_config
-        );
+            for DiscoveredFan { label, fan } in &fans {
+                let config = get_config(fan)
+                    .with_context(|| format!("Error reading {label} config"))?;
+                eprintln!("{}", config);
+            }
+            eprintln!("Failed to open fan hardware as read-write.\nPlease run this program as root to control the fans.");
+        }
     }
 
     Ok(())
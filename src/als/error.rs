@@ -0,0 +1,26 @@
+//! Error types and messages for the als module.
+
+use crate::{common_hardware::kbd_blacklight::KbdBacklightError, debugfs::error::HardwareError};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AlsError {
+    #[error("No ambient light sensor found under `/sys/bus/iio/devices`!")]
+    NotFound,
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error("{0}")]
+    KbdBacklightError(#[from] KbdBacklightError),
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
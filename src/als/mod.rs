@@ -0,0 +1,228 @@
+//! ASUS_WMI_DEVID_ALS_ENABLE
+//!
+//! Ambient light sensor, enabled through asus-wmi and read through its IIO device.
+//! [AutoBrightness] drives the keyboard backlight from the measured illuminance.
+
+pub mod error;
+
+use crate::{
+    auto_impl_config,
+    common_hardware::kbd_blacklight::KbdBacklight,
+    debugfs::{Config, Hardware},
+    error::StateError,
+    sysfs,
+    worker::Worker,
+};
+use error::AlsError;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub const DEV_ID: u64 = 0x00050001;
+
+const IIO_PATH: &str = "/sys/bus/iio/devices";
+
+auto_impl_config!(AlsState, u8, Disabled = 0, Enabled = 1);
+
+pub const fn get() -> Hardware<AlsState> {
+    Hardware::new(DEV_ID)
+}
+
+/// Ambient light sensor exposed as an IIO device.
+#[derive(Debug, Clone)]
+pub struct AmbientLightSensor {
+    path: PathBuf,
+}
+
+impl AmbientLightSensor {
+    /// Use the IIO device at `path`, such as `/sys/bus/iio/devices/iio:device0`.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, AlsError> {
+        let path = path.into();
+        if path.join("in_illuminance_raw").exists() {
+            Ok(AmbientLightSensor { path })
+        } else {
+            Err(AlsError::NotFound)
+        }
+    }
+
+    /// Find the first IIO device providing `in_illuminance_raw`.
+    pub fn find() -> Result<Self, AlsError> {
+        let entries = fs::read_dir(IIO_PATH).map_err(|error| AlsError::ReadFailed {
+            path: IIO_PATH.into(),
+            error,
+        })?;
+
+        let mut devices: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.join("in_illuminance_raw").exists())
+            .collect();
+        devices.sort();
+
+        devices
+            .into_iter()
+            .next()
+            .map(|path| AmbientLightSensor { path })
+            .ok_or(AlsError::NotFound)
+    }
+
+    /// Enable the sensor through asus-wmi, then find its IIO device.
+    pub fn enable() -> Result<Self, AlsError> {
+        get().apply(AlsState::Enabled)?;
+        Self::find()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_attr(&self, name: &str) -> Result<Option<f64>, AlsError> {
+        let path = self.path.join(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let value = sysfs::read_trimmed(&path).map_err(|error| AlsError::ReadFailed {
+            path: path.clone(),
+            error,
+        })?;
+        value
+            .parse()
+            .map(Some)
+            .map_err(|_| AlsError::UnexpectedValue { path, value })
+    }
+
+    /// Illuminance in lux, `(raw + offset) * scale`.
+    pub fn lux(&self) -> Result<f64, AlsError> {
+        let raw = self
+            .read_attr("in_illuminance_raw")?
+            .ok_or(AlsError::NotFound)?;
+        let offset = self.read_attr("in_illuminance_offset")?.unwrap_or(0.0);
+        let scale = self.read_attr("in_illuminance_scale")?.unwrap_or(1.0);
+        Ok((raw + offset) * scale)
+    }
+}
+
+/// Keyboard backlight `level` used while the illuminance is below `below` lux.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LuxThreshold {
+    pub below: f64,
+    pub level: u8,
+}
+
+/// Sets the keyboard backlight level from the ambient illuminance.
+///
+/// The thresholds are kept sorted by lux; above the last one the backlight is off.
+/// A level only changes once the illuminance crosses a threshold by more than
+/// `hysteresis` lux, so the backlight doesn't flicker around a boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoBrightness {
+    thresholds: Vec<LuxThreshold>,
+    /// Lux beyond a threshold before switching levels.
+    pub hysteresis: f64,
+    /// How often the sensor is read.
+    pub interval: Duration,
+}
+
+impl Default for AutoBrightness {
+    fn default() -> Self {
+        AutoBrightness::new(vec![
+            LuxThreshold {
+                below: 10.0,
+                level: 3,
+            },
+            LuxThreshold {
+                below: 30.0,
+                level: 2,
+            },
+            LuxThreshold {
+                below: 80.0,
+                level: 1,
+            },
+        ])
+    }
+}
+
+impl AutoBrightness {
+    pub fn new(mut thresholds: Vec<LuxThreshold>) -> Self {
+        thresholds.sort_by(|a, b| a.below.total_cmp(&b.below));
+        AutoBrightness {
+            thresholds,
+            hysteresis: 5.0,
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    pub fn thresholds(&self) -> &[LuxThreshold] {
+        &self.thresholds
+    }
+
+    fn range_of(&self, lux: f64) -> usize {
+        self.thresholds
+            .iter()
+            .position(|t| lux < t.below)
+            .unwrap_or(self.thresholds.len())
+    }
+
+    fn level_of(&self, range: usize) -> u8 {
+        self.thresholds.get(range).map_or(0, |t| t.level)
+    }
+
+    /// Range index for `lux`, staying in `current` unless past the hysteresis.
+    fn next_range(&self, lux: f64, current: Option<usize>) -> usize {
+        let target = self.range_of(lux);
+        match current {
+            Some(current) if target > current => self.range_of(lux - self.hysteresis).max(current),
+            Some(current) if target < current => self.range_of(lux + self.hysteresis).min(current),
+            _ => target,
+        }
+    }
+
+    /// Start driving `backlight` from `sensor` on a background thread.
+    ///
+    /// Levels are clamped to the maximum of the backlight.
+    pub fn start(
+        self,
+        sensor: &AmbientLightSensor,
+        backlight: &KbdBacklight,
+    ) -> Worker<Result<(), AlsError>> {
+        let sensor = sensor.clone();
+        let backlight = backlight.clone();
+
+        Worker::spawn(move |signal| {
+            let mut current = None;
+            loop {
+                let range = self.next_range(sensor.lux()?, current);
+                if current != Some(range) {
+                    backlight.set(self.level_of(range).min(backlight.max()))?;
+                    current = Some(range);
+                }
+                if signal.sleep(self.interval) {
+                    return Ok(());
+                }
+            }
+        })
+    }
+}
+
+#[test]
+fn auto_brightness_hysteresis() {
+    let auto = AutoBrightness::default();
+    let level = |lux, current| auto.level_of(auto.next_range(lux, current));
+
+    assert_eq!(level(0.0, None), 3);
+    assert_eq!(level(50.0, None), 1);
+    assert_eq!(level(500.0, None), 0);
+
+    // just past a threshold, stays in the current range
+    let dark = Some(auto.range_of(5.0));
+    assert_eq!(level(12.0, dark), 3);
+    assert_eq!(level(16.0, dark), 2);
+
+    let bright = Some(auto.range_of(500.0));
+    assert_eq!(level(78.0, bright), 0);
+    assert_eq!(level(74.0, bright), 1);
+    assert_eq!(level(2.0, bright), 3);
+}
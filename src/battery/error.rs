@@ -0,0 +1,32 @@
+//! Error types and messages for the battery module.
+
+use crate::debugfs::error::HardwareError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BatteryError {
+    #[error("No battery found under `/sys/class/power_supply`!")]
+    NotFound,
+
+    #[error("Battery charge limit is not supported on this machine!")]
+    ChargeLimitUnsupported,
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to write `{path:?}`! {error}")]
+    WriteFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
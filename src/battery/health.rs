@@ -0,0 +1,82 @@
+//! Battery status and health as reported by the power_supply class.
+
+use super::{error::BatteryError, Battery};
+use std::str::FromStr;
+
+/// Charging status of the battery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryStatus {
+    Charging,
+    Discharging,
+    NotCharging,
+    Full,
+    Unknown,
+}
+
+impl FromStr for BatteryStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "Charging" => Ok(BatteryStatus::Charging),
+            "Discharging" => Ok(BatteryStatus::Discharging),
+            "Not charging" => Ok(BatteryStatus::NotCharging),
+            "Full" => Ok(BatteryStatus::Full),
+            "Unknown" => Ok(BatteryStatus::Unknown),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Wear of the battery compared to its design capacity.
+///
+/// Capacities are in µWh when the battery reports energy, and in µAh when it
+/// only reports charge, both ratios being comparable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatteryHealth {
+    pub full: u64,
+    pub full_design: u64,
+    pub cycle_count: Option<u32>,
+}
+
+impl BatteryHealth {
+    pub(super) fn read(battery: &Battery) -> Result<Self, BatteryError> {
+        let (full, full_design) = match battery.read_parsed("energy_full") {
+            Ok(full) => (full, battery.read_parsed("energy_full_design")?),
+            Err(_) => (
+                battery.read_parsed("charge_full")?,
+                battery.read_parsed("charge_full_design")?,
+            ),
+        };
+
+        // some firmwares report 0 when they don't track cycles
+        let cycle_count = battery
+            .read_parsed::<u32>("cycle_count")
+            .ok()
+            .filter(|count| *count > 0);
+
+        Ok(BatteryHealth {
+            full,
+            full_design,
+            cycle_count,
+        })
+    }
+
+    /// Remaining capacity compared to the design capacity, in percent.
+    pub fn percent(&self) -> f32 {
+        if self.full_design == 0 {
+            return 0.0;
+        }
+        self.full as f32 * 100.0 / self.full_design as f32
+    }
+}
+
+#[test]
+fn health_percent() {
+    let health = BatteryHealth {
+        full: 42_000_000,
+        full_design: 50_000_000,
+        cycle_count: None,
+    };
+    assert_eq!(health.percent(), 84.0);
+}
@@ -0,0 +1,359 @@
+//! Battery charge limit and health.
+//!
+//! The charge limit (end threshold) can be set either through the power_supply class
+//! `charge_control_end_threshold` attribute exposed by asus-wmi, or directly with
+//! ASUS_WMI_DEVID_RSOC through debugfs on kernels which don't expose the attribute.
+//! The power_supply attribute is preferred when present, since the driver then keeps
+//! track of the limit (and restores it after resume).
+
+pub mod error;
+mod health;
+
+pub use health::{BatteryHealth, BatteryStatus};
+
+use crate::{
+    debugfs::{error::StateError, Config, Hardware},
+    persist::STATE_DIR,
+    sysfs,
+    worker::{StopSignal, Worker},
+};
+use error::BatteryError;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// ASUS_WMI_DEVID_RSOC
+pub const DEV_ID: u64 = 0x00120057;
+
+const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+
+/// File under [STATE_DIR] keeping the limit to restore after charging to full.
+const SAVED_LIMIT_FILE: &str = "charge_limit";
+
+/// Source the machine is currently running on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+}
+
+/// Find whether the machine runs on AC or battery, from the `Mains` power supplies.
+pub fn power_source() -> Result<PowerSource, BatteryError> {
+    let entries = fs::read_dir(POWER_SUPPLY_PATH).map_err(|error| BatteryError::ReadFailed {
+        path: POWER_SUPPLY_PATH.into(),
+        error,
+    })?;
+
+    let ac_online = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| sysfs::read_trimmed(path.join("type")).is_ok_and(|t| t == "Mains"))
+        .any(|path| sysfs::read_trimmed(path.join("online")).is_ok_and(|online| online == "1"));
+
+    Ok(if ac_online {
+        PowerSource::Ac
+    } else {
+        PowerSource::Battery
+    })
+}
+
+/// Battery charge limit in percent, ranging from 1 to 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChargeLimit(u8);
+
+impl ChargeLimit {
+    pub const MIN: u8 = 1;
+    pub const MAX: u8 = 100;
+    /// No limit, charge up to 100%.
+    pub const FULL: ChargeLimit = ChargeLimit(100);
+
+    pub const fn new(percent: u8) -> Option<Self> {
+        if percent >= Self::MIN && percent <= Self::MAX {
+            Some(ChargeLimit(percent))
+        } else {
+            None
+        }
+    }
+
+    pub const fn percent(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u64> for ChargeLimit {
+    type Error = StateError;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        u8::try_from(value)
+            .ok()
+            .and_then(ChargeLimit::new)
+            .ok_or(StateError::NotPossibleState { value })
+    }
+}
+
+impl Config for ChargeLimit {
+    fn to_config(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+pub const fn get() -> Hardware<ChargeLimit> {
+    Hardware::new(DEV_ID)
+}
+
+fn saved_limit_path() -> PathBuf {
+    Path::new(STATE_DIR).join(SAVED_LIMIT_FILE)
+}
+
+/// Read the limit saved by [Battery::charge_to_full_once], `None` if nothing was saved.
+fn load_saved_limit() -> Result<Option<ChargeLimit>, BatteryError> {
+    let path = saved_limit_path();
+    let value = match sysfs::read_trimmed(&path) {
+        Ok(value) => value,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(BatteryError::ReadFailed { path, error }),
+    };
+    value
+        .parse::<u64>()
+        .ok()
+        .and_then(|v| ChargeLimit::try_from(v).ok())
+        .map(Some)
+        .ok_or(BatteryError::UnexpectedValue { path, value })
+}
+
+fn save_limit(limit: ChargeLimit) -> Result<(), BatteryError> {
+    let path = saved_limit_path();
+    fs::create_dir_all(STATE_DIR)
+        .and_then(|_| sysfs::write(&path, limit.percent()))
+        .map_err(|error| BatteryError::WriteFailed { path, error })
+}
+
+fn forget_saved_limit() -> Result<(), BatteryError> {
+    let path = saved_limit_path();
+    match fs::remove_file(&path) {
+        Err(error) if error.kind() != ErrorKind::NotFound => {
+            Err(BatteryError::WriteFailed { path, error })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Interface used to control the charge limit.
+#[derive(Debug, Clone)]
+pub enum ChargeLimitBackend {
+    /// `charge_control_end_threshold` attribute of the battery.
+    PowerSupply(PathBuf),
+    /// ASUS_WMI_DEVID_RSOC through debugfs.
+    Wmi(Hardware<ChargeLimit>),
+}
+
+/// A battery under `/sys/class/power_supply`.
+#[derive(Debug, Clone)]
+pub struct Battery {
+    path: PathBuf,
+}
+
+impl Battery {
+    /// Use the battery at the given power_supply path (example: `/sys/class/power_supply/BAT0`).
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Battery { path: path.into() }
+    }
+
+    /// Find the first `BAT*` power supply.
+    pub fn find() -> Result<Self, BatteryError> {
+        let entries =
+            fs::read_dir(POWER_SUPPLY_PATH).map_err(|error| BatteryError::ReadFailed {
+                path: POWER_SUPPLY_PATH.into(),
+                error,
+            })?;
+
+        let mut batteries: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("BAT"))
+            .map(|entry| entry.path())
+            .collect();
+        batteries.sort();
+
+        batteries
+            .into_iter()
+            .next()
+            .map(Battery::new)
+            .ok_or(BatteryError::NotFound)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn read_attr(&self, name: &str) -> Result<String, BatteryError> {
+        let path = self.path.join(name);
+        sysfs::read_trimmed(&path).map_err(|error| BatteryError::ReadFailed { path, error })
+    }
+
+    pub(crate) fn read_parsed<T: FromStr>(&self, name: &str) -> Result<T, BatteryError> {
+        let value = self.read_attr(name)?;
+        value.parse().map_err(|_| BatteryError::UnexpectedValue {
+            path: self.path.join(name),
+            value,
+        })
+    }
+
+    fn has_attr(&self, name: &str) -> bool {
+        self.path.join(name).exists()
+    }
+
+    /// Current charge in percent.
+    pub fn capacity(&self) -> Result<u8, BatteryError> {
+        self.read_parsed("capacity")
+    }
+
+    /// Current charging status.
+    pub fn status(&self) -> Result<BatteryStatus, BatteryError> {
+        let value = self.read_attr("status")?;
+        value.parse().map_err(|_| BatteryError::UnexpectedValue {
+            path: self.path.join("status"),
+            value,
+        })
+    }
+
+    /// Backend to use for the charge limit, preferring the power_supply attribute.
+    pub fn charge_limit_backend(&self) -> Result<ChargeLimitBackend, BatteryError> {
+        if self.has_attr("charge_control_end_threshold") {
+            return Ok(ChargeLimitBackend::PowerSupply(
+                self.path.join("charge_control_end_threshold"),
+            ));
+        }
+
+        let hardware = get();
+        if hardware.is_present()? {
+            Ok(ChargeLimitBackend::Wmi(hardware))
+        } else {
+            Err(BatteryError::ChargeLimitUnsupported)
+        }
+    }
+
+    /// Read the current charge limit.
+    pub fn charge_limit(&self) -> Result<ChargeLimit, BatteryError> {
+        match self.charge_limit_backend()? {
+            ChargeLimitBackend::PowerSupply(path) => {
+                let value =
+                    sysfs::read_trimmed(&path).map_err(|error| BatteryError::ReadFailed {
+                        path: path.clone(),
+                        error,
+                    })?;
+                value
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|v| ChargeLimit::try_from(v).ok())
+                    .ok_or(BatteryError::UnexpectedValue { path, value })
+            }
+            // the presence bits live above the lowest byte
+            ChargeLimitBackend::Wmi(hardware) => Ok(hardware.read_masked(0xFF)?),
+        }
+    }
+
+    /// Apply the given charge limit.
+    pub fn set_charge_limit(&self, limit: ChargeLimit) -> Result<(), BatteryError> {
+        match self.charge_limit_backend()? {
+            ChargeLimitBackend::PowerSupply(path) => sysfs::write(&path, limit.percent())
+                .map_err(|error| BatteryError::WriteFailed { path, error }),
+            ChargeLimitBackend::Wmi(hardware) => Ok(hardware.apply(limit)?),
+        }
+    }
+
+    /// Charge to 100% once on a background thread, then restore the current charge limit.
+    ///
+    /// The worker finishes when the battery reports [Full](BatteryStatus::Full) or 100%
+    /// capacity, the charger is unplugged, or it is stopped, checking every `poll_interval`.
+    ///
+    /// The previous limit is stored under [STATE_DIR] before
+    /// raising it, and restored even if reading the battery state fails midway. If the
+    /// process dies meanwhile, [restore_charge_limit](Battery::restore_charge_limit) brings
+    /// it back later.
+    pub fn charge_to_full_once(&self, poll_interval: Duration) -> Worker<Result<(), BatteryError>> {
+        let battery = self.clone();
+        Worker::spawn(move |signal| {
+            // an interrupted run leaves the limit raised, keep the one it saved
+            let limit = match load_saved_limit()? {
+                Some(limit) => limit,
+                None => battery.charge_limit()?,
+            };
+            save_limit(limit)?;
+
+            let charged = battery
+                .set_charge_limit(ChargeLimit::FULL)
+                .and_then(|_| battery.wait_until_charged(poll_interval, &signal));
+            let restored = battery.restore_charge_limit();
+
+            charged.and(restored.map(|_| ()))
+        })
+    }
+
+    /// Restore the limit saved by [charge_to_full_once](Battery::charge_to_full_once), if it
+    /// didn't get to restore it, returning it.
+    pub fn restore_charge_limit(&self) -> Result<Option<ChargeLimit>, BatteryError> {
+        let Some(limit) = load_saved_limit()? else {
+            return Ok(None);
+        };
+        self.set_charge_limit(limit)?;
+        forget_saved_limit()?;
+        Ok(Some(limit))
+    }
+
+    fn wait_until_charged(
+        &self,
+        poll_interval: Duration,
+        signal: &StopSignal,
+    ) -> Result<(), BatteryError> {
+        loop {
+            match self.status()? {
+                BatteryStatus::Full | BatteryStatus::Discharging => return Ok(()),
+                _ if self.capacity()? >= ChargeLimit::MAX => return Ok(()),
+                _ if signal.sleep(poll_interval) => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Read the health of the battery.
+    pub fn health(&self) -> Result<BatteryHealth, BatteryError> {
+        BatteryHealth::read(self)
+    }
+}
+
+#[test]
+fn charge_limit_range() {
+    assert_eq!(ChargeLimit::try_from(80).unwrap().percent(), 80);
+    assert!(ChargeLimit::try_from(0).is_err());
+    assert!(ChargeLimit::try_from(101).is_err());
+    assert!(ChargeLimit::try_from(0x10050).is_err());
+}
+
+#[test]
+fn charge_limit() {
+    let battery = Battery::find().expect("there should be a battery");
+
+    let initial_limit = battery
+        .charge_limit()
+        .expect("there should be a current charge limit");
+
+    let limit = ChargeLimit::new(80).unwrap();
+    battery
+        .set_charge_limit(limit)
+        .expect("charge limit should be set");
+    assert_eq!(battery.charge_limit().unwrap(), limit);
+
+    // return to initial limit
+    battery
+        .set_charge_limit(initial_limit)
+        .expect("charge limit should be switched to initial limit");
+    assert_eq!(
+        battery.charge_limit().unwrap(),
+        initial_limit,
+        "Failed to revert to initial limit"
+    );
+}
@@ -0,0 +1,33 @@
+//! Error types and messages for the camera module.
+
+use super::CameraState;
+use crate::debugfs::error::HardwareError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CameraError {
+    #[error("No camera control found, neither ASUS_WMI_DEVID_CAMERA nor the uvcvideo driver!")]
+    NotFound,
+
+    #[error("No camera interface recorded to bind again, was the camera disabled by this crate?")]
+    NothingToRebind,
+
+    #[error("The camera was set `{expected:?}` but `/dev/video*` devices don't reflect it!")]
+    VerificationFailed { expected: CameraState },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to write `{path:?}`! {error}")]
+    WriteFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
@@ -0,0 +1,330 @@
+//! Camera privacy controller.
+//!
+//! Disables the camera through ASUS_WMI_DEVID_CAMERA where the firmware has it, or else
+//! unbinds the USB camera interfaces from the `uvcvideo` driver. The result is verified
+//! by waiting for the `/dev/video*` devices to disappear (or come back), and
+//! [camera LED](crate::common_hardware::camera_led) is kept lit while the camera is disabled.
+//!
+//! [CameraUsageMonitor] instead uses the camera led to tell when the camera is in use.
+
+pub mod error;
+mod usage;
+
+pub use usage::{camera_users, CameraUsageMonitor, CameraUser};
+
+use crate::{
+    auto_impl_config,
+    common_hardware::{camera_led, led_state::LedState},
+    debugfs::{Config, Hardware},
+    error::StateError,
+    sysfs,
+};
+use error::CameraError;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+/// ASUS_WMI_DEVID_CAMERA
+pub const DEV_ID: u64 = 0x00060013;
+
+const UVC_DRIVER_PATH: &str = "/sys/bus/usb/drivers/uvcvideo";
+const DEV_PATH: &str = "/dev";
+const V4L_PATH: &str = "/sys/class/video4linux";
+const RUN_DIR: &str = "/run/meh-asus";
+/// Interfaces unbound by this crate, to bind them again.
+const UNBOUND_PATH: &str = "/run/meh-asus/camera_unbound";
+
+/// Time allowed for the `/dev/video*` devices to follow the change.
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(5);
+
+auto_impl_config!(CameraState, u8, Disabled = 0, Enabled = 1);
+
+pub const fn get() -> Hardware<CameraState> {
+    Hardware::new(DEV_ID)
+}
+
+/// Interface used to disable the camera.
+#[derive(Debug, Clone)]
+pub enum CameraBackend {
+    /// ASUS_WMI_DEVID_CAMERA through debugfs.
+    Wmi(Hardware<CameraState>),
+    /// Unbinding the USB interfaces from the `uvcvideo` driver.
+    UsbUnbind,
+}
+
+/// Camera privacy controller.
+#[derive(Debug, Clone)]
+pub struct Camera {
+    backend: CameraBackend,
+    sync_led: bool,
+}
+
+impl Camera {
+    /// Use the given backend, keeping the camera led in sync.
+    pub const fn new(backend: CameraBackend) -> Self {
+        Camera {
+            backend,
+            sync_led: true,
+        }
+    }
+
+    /// Prefer the WMI DEV_ID, falling back to unbinding from `uvcvideo`.
+    pub fn detect() -> Result<Self, CameraError> {
+        let hardware = get();
+        if hardware.is_present()? {
+            Ok(Self::new(CameraBackend::Wmi(hardware)))
+        } else if Path::new(UVC_DRIVER_PATH).exists() {
+            Ok(Self::new(CameraBackend::UsbUnbind))
+        } else {
+            Err(CameraError::NotFound)
+        }
+    }
+
+    /// Whether to keep the camera led lit while the camera is disabled.
+    pub fn sync_led(mut self, sync_led: bool) -> Self {
+        self.sync_led = sync_led;
+        self
+    }
+
+    pub const fn backend(&self) -> &CameraBackend {
+        &self.backend
+    }
+
+    /// Read the current state of the camera.
+    pub fn state(&self) -> Result<CameraState, CameraError> {
+        match &self.backend {
+            CameraBackend::Wmi(hardware) => Ok(hardware.read_masked(0x1)?),
+            CameraBackend::UsbUnbind => Ok(if bound_interfaces()?.is_empty() {
+                CameraState::Disabled
+            } else {
+                CameraState::Enabled
+            }),
+        }
+    }
+
+    /// Enable or disable the camera, and wait for its video devices to follow.
+    ///
+    /// Only the video devices of the camera are checked, other V4L devices (such as
+    /// capture cards or v4l2loopback) are left out.
+    pub fn set(&self, state: CameraState) -> Result<(), CameraError> {
+        if self.state()? == state {
+            return self.update_led();
+        }
+
+        let before = camera_nodes()?;
+        // USB interfaces of the camera, only known to the unbind backend
+        let interfaces = match &self.backend {
+            CameraBackend::Wmi(hardware) => {
+                hardware.apply(state)?;
+                None
+            }
+            CameraBackend::UsbUnbind => Some(match state {
+                CameraState::Disabled => unbind()?,
+                CameraState::Enabled => rebind()?,
+            }),
+        };
+
+        let verified = wait_for_camera_nodes(|nodes| {
+            let of_camera = |node: &VideoNode| match &interfaces {
+                Some(interfaces) => interfaces.contains(&node.interface),
+                None => true,
+            };
+            match (&interfaces, state) {
+                (Some(_), CameraState::Disabled) => !nodes.iter().any(of_camera),
+                (Some(_), CameraState::Enabled) => nodes.iter().any(of_camera),
+                // with WMI the camera is the USB device going away or coming back
+                (None, CameraState::Disabled) => {
+                    before.is_empty() || before.iter().any(|node| !nodes.contains(node))
+                }
+                (None, CameraState::Enabled) => nodes.iter().any(|node| !before.contains(node)),
+            }
+        })?;
+        self.update_led()?;
+
+        if verified {
+            Ok(())
+        } else {
+            Err(CameraError::VerificationFailed { expected: state })
+        }
+    }
+
+    /// Switch the camera to the other state and return it.
+    pub fn toggle(&self) -> Result<CameraState, CameraError> {
+        let state = match self.state()? {
+            CameraState::Enabled => CameraState::Disabled,
+            CameraState::Disabled => CameraState::Enabled,
+        };
+        self.set(state)?;
+        Ok(state)
+    }
+
+    /// Set the camera led from the real state of the camera: lit while disabled.
+    pub fn update_led(&self) -> Result<(), CameraError> {
+        if !self.sync_led {
+            return Ok(());
+        }
+
+        let led = camera_led::get();
+        if !led.is_present()? {
+            return Ok(());
+        }
+
+        led.apply(match self.state()? {
+            CameraState::Enabled => LedState::Off,
+            CameraState::Disabled => LedState::On,
+        })?;
+        Ok(())
+    }
+}
+
+/// Paths of the `/dev/video*` devices.
+pub fn video_devices() -> Result<Vec<PathBuf>, CameraError> {
+    let entries = fs::read_dir(DEV_PATH).map_err(|error| CameraError::ReadFailed {
+        path: DEV_PATH.into(),
+        error,
+    })?;
+
+    let mut devices: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("video"))
+        .map(|entry| entry.path())
+        .collect();
+    devices.sort();
+    Ok(devices)
+}
+
+/// A V4L device of `uvcvideo`, with the USB interface it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VideoNode {
+    name: String,
+    interface: String,
+}
+
+/// V4L devices provided by `uvcvideo`.
+fn camera_nodes() -> Result<Vec<VideoNode>, CameraError> {
+    let entries = match fs::read_dir(V4L_PATH) {
+        Ok(entries) => entries,
+        // no V4L device at all
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(CameraError::ReadFailed {
+                path: V4L_PATH.into(),
+                error,
+            })
+        }
+    };
+
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let device = entry.path().join("device");
+            let driver = fs::canonicalize(device.join("driver")).ok()?;
+            if driver.file_name()? != "uvcvideo" {
+                return None;
+            }
+
+            Some(VideoNode {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                interface: fs::canonicalize(device)
+                    .ok()?
+                    .file_name()?
+                    .to_string_lossy()
+                    .into_owned(),
+            })
+        })
+        .collect())
+}
+
+fn wait_for_camera_nodes(done: impl Fn(&[VideoNode]) -> bool) -> Result<bool, CameraError> {
+    let start = Instant::now();
+    loop {
+        if done(&camera_nodes()?) {
+            return Ok(true);
+        }
+        if start.elapsed() >= VERIFY_TIMEOUT {
+            return Ok(false);
+        }
+        sleep(Duration::from_millis(100));
+    }
+}
+
+/// USB interfaces bound to `uvcvideo` (such as `1-5:1.0`).
+fn bound_interfaces() -> Result<Vec<String>, CameraError> {
+    let entries = fs::read_dir(UVC_DRIVER_PATH).map_err(|error| CameraError::ReadFailed {
+        path: UVC_DRIVER_PATH.into(),
+        error,
+    })?;
+
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| {
+            name.contains(':') && name.chars().next().is_some_and(|c| c.is_ascii_digit())
+        })
+        .collect())
+}
+
+fn write_driver_attr(name: &str, interface: &str) -> Result<(), CameraError> {
+    let path = Path::new(UVC_DRIVER_PATH).join(name);
+    sysfs::write(&path, interface).map_err(|error| CameraError::WriteFailed { path, error })
+}
+
+/// Unbind the camera interfaces, returning them.
+fn unbind() -> Result<Vec<String>, CameraError> {
+    let interfaces = bound_interfaces()?;
+    if interfaces.is_empty() {
+        return Ok(interfaces);
+    }
+
+    // record before unbinding, so the camera can always be brought back
+    let mut recorded = read_unbound()?;
+    recorded.extend(interfaces.iter().cloned());
+    recorded.sort();
+    recorded.dedup();
+    fs::create_dir_all(RUN_DIR)
+        .and_then(|_| sysfs::write(UNBOUND_PATH, recorded.join("\n")))
+        .map_err(|error| CameraError::WriteFailed {
+            path: UNBOUND_PATH.into(),
+            error,
+        })?;
+
+    for interface in &interfaces {
+        write_driver_attr("unbind", interface)?;
+    }
+    Ok(interfaces)
+}
+
+/// Bind back the recorded camera interfaces, returning them.
+fn rebind() -> Result<Vec<String>, CameraError> {
+    let interfaces = read_unbound()?;
+    if interfaces.is_empty() {
+        return Err(CameraError::NothingToRebind);
+    }
+
+    for interface in &interfaces {
+        write_driver_attr("bind", interface)?;
+    }
+
+    match fs::remove_file(UNBOUND_PATH) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(CameraError::WriteFailed {
+            path: UNBOUND_PATH.into(),
+            error,
+        }),
+        _ => Ok(interfaces),
+    }
+}
+
+fn read_unbound() -> Result<Vec<String>, CameraError> {
+    match sysfs::read_trimmed(UNBOUND_PATH) {
+        Ok(content) => Ok(content.lines().map(str::to_string).collect()),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(CameraError::ReadFailed {
+            path: UNBOUND_PATH.into(),
+            error,
+        }),
+    }
+}
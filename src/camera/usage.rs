@@ -0,0 +1,139 @@
+//! Camera-in-use indicator, driven by the processes holding `/dev/video*` open.
+
+use super::error::CameraError;
+use crate::{
+    common_hardware::{camera_led, led_state::LedState},
+    sysfs,
+    worker::Worker,
+};
+use std::{fs, path::PathBuf, time::Duration};
+
+const PROC_PATH: &str = "/proc";
+
+/// A process holding a camera device open.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CameraUser {
+    pub pid: u32,
+    /// Command name, from `/proc/PID/comm`.
+    pub name: String,
+    pub device: PathBuf,
+}
+
+/// Find the processes holding a `/dev/video*` device open, by scanning `/proc/*/fd`.
+///
+/// Processes whose file descriptors can't be read (owned by other users without root)
+/// are skipped.
+pub fn camera_users() -> Result<Vec<CameraUser>, CameraError> {
+    let entries = fs::read_dir(PROC_PATH).map_err(|error| CameraError::ReadFailed {
+        path: PROC_PATH.into(),
+        error,
+    })?;
+
+    let mut users = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let Some(pid) = entry.file_name().to_str().and_then(|pid| pid.parse().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        let mut devices: Vec<PathBuf> = fds
+            .filter_map(|fd| fs::read_link(fd.ok()?.path()).ok())
+            .filter(|target| {
+                target
+                    .to_str()
+                    .is_some_and(|target| target.starts_with("/dev/video"))
+            })
+            .collect();
+        devices.sort();
+        devices.dedup();
+
+        if devices.is_empty() {
+            continue;
+        }
+        let name = sysfs::read_trimmed(entry.path().join("comm")).unwrap_or_default();
+        users.extend(devices.into_iter().map(|device| CameraUser {
+            pid,
+            name: name.clone(),
+            device,
+        }));
+    }
+
+    users.sort();
+    Ok(users)
+}
+
+/// Lights [camera_led] while any process holds the camera open, giving a hardware
+/// privacy indicator on models whose camera has no LED of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CameraUsageMonitor {
+    /// How often `/proc` is scanned.
+    pub interval: Duration,
+    /// Whether to drive the camera led, or only report the users.
+    pub drive_led: bool,
+}
+
+impl Default for CameraUsageMonitor {
+    fn default() -> Self {
+        CameraUsageMonitor {
+            interval: Duration::from_secs(1),
+            drive_led: true,
+        }
+    }
+}
+
+impl CameraUsageMonitor {
+    /// Start scanning on a background thread, calling `on_change` with the current users
+    /// whenever they change (with an empty list once the camera is released).
+    ///
+    /// The camera led is switched off when the worker is stopped.
+    pub fn start(
+        self,
+        mut on_change: impl FnMut(&[CameraUser]) + Send + 'static,
+    ) -> Worker<Result<(), CameraError>> {
+        Worker::spawn(move |signal| {
+            let led = camera_led::get();
+            let mut last: Vec<CameraUser> = Vec::new();
+
+            let result = loop {
+                let users = match camera_users() {
+                    Ok(users) => users,
+                    Err(e) => break Err(e),
+                };
+
+                if users != last {
+                    if self.drive_led && users.is_empty() != last.is_empty() {
+                        let state = if users.is_empty() {
+                            LedState::Off
+                        } else {
+                            LedState::On
+                        };
+                        if let Err(e) = led.apply(state) {
+                            break Err(e.into());
+                        }
+                    }
+                    on_change(&users);
+                    last = users;
+                }
+
+                if signal.sleep(self.interval) {
+                    break Ok(());
+                }
+            };
+
+            if self.drive_led && !last.is_empty() {
+                led.apply(LedState::Off)?;
+            }
+            result
+        })
+    }
+}
+
+#[test]
+fn scan_camera_users() {
+    let users = camera_users().expect("/proc should be readable");
+    assert!(users
+        .iter()
+        .all(|user| user.device.starts_with("/dev") && user.pid > 0));
+}
@@ -0,0 +1,58 @@
+//! ASUS_WMI_DEVID_BOOT_SOUND
+//!
+//! POST boot sound played by the firmware when powering on.
+
+use crate::{
+    auto_impl_config,
+    debugfs::{error::HardwareError, Config, Hardware},
+    error::StateError,
+    persist::Persistent,
+};
+
+pub const DEV_ID: u64 = 0x00130022;
+
+pub const fn get() -> Hardware<BootSound> {
+    Hardware::new(DEV_ID)
+}
+
+/// Check the boot sound is present on this machine.
+pub fn is_present() -> Result<bool, HardwareError> {
+    get().is_present()
+}
+
+/// Boot sound with its state stored under [STATE_DIR](crate::persist::STATE_DIR).
+pub fn persistent() -> Persistent<BootSound> {
+    Persistent::new("boot_sound", get()).masked(0x1)
+}
+
+auto_impl_config!(BootSound, u8, Off = 0, On = 1);
+
+#[test]
+fn boot_sound() {
+    let boot_sound = get();
+
+    let initial_state = boot_sound
+        .read()
+        .expect("there should be a current state of boot sound");
+
+    boot_sound
+        .apply(BootSound::Off)
+        .expect("boot sound should be turned off");
+    assert_eq!(boot_sound.read().unwrap(), BootSound::Off);
+
+    boot_sound
+        .apply(BootSound::On)
+        .expect("boot sound should be turned on");
+    assert_eq!(boot_sound.read().unwrap(), BootSound::On);
+
+    // return to initial state
+    boot_sound
+        .apply(initial_state)
+        .expect("boot sound should be switched to initial state");
+
+    assert_eq!(
+        boot_sound.read().unwrap(),
+        initial_state,
+        "Failed to revert to initial state"
+    );
+}
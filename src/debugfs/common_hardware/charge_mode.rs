@@ -0,0 +1,38 @@
+//! ASUS_WMI_DEVID_CHARGE_MODE
+//!
+//! Charger currently in use, as reported by the firmware. The value is read-only,
+//! so unlike the toggles it has no [Persistent](crate::persist::Persistent) state.
+
+use crate::{
+    auto_impl_config,
+    debugfs::{error::HardwareError, Config, Hardware},
+    error::StateError,
+};
+
+pub const DEV_ID: u64 = 0x0012006C;
+
+/// Bits of the DSTS value holding the charge mode.
+pub const MODE_MASK: u64 = 0x03;
+
+pub const fn get() -> Hardware<ChargeMode> {
+    Hardware::new(DEV_ID)
+}
+
+/// Check the charge mode is present on this machine.
+pub fn is_present() -> Result<bool, HardwareError> {
+    get().is_present()
+}
+
+/// Read the charge mode without writing to the firmware.
+pub fn read() -> Result<ChargeMode, HardwareError> {
+    get().read_masked(MODE_MASK)
+}
+
+auto_impl_config!(
+    ChargeMode,
+    u8,
+    NotCharging = 0,
+    Barrel = 1,
+    UsbC = 2,
+    Both = 3
+);
@@ -0,0 +1,94 @@
+//! Display settings tied to the power source.
+//!
+//! Combines [panel overdrive](super::panel_od) and [mini LED](super::mini_led) into profiles,
+//! such as turning the mini-LED off while on battery.
+
+use super::{
+    mini_led::{MiniLed, MiniLedError, MiniLedMode},
+    panel_od::{self, PanelOverdrive},
+};
+use crate::{
+    battery::{self, error::BatteryError, PowerSource},
+    debugfs::error::HardwareError,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DisplayProfileError {
+    #[error("{0}")]
+    MiniLedError(#[from] MiniLedError),
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+
+    #[error("{0}")]
+    BatteryError(#[from] BatteryError),
+}
+
+/// Display settings to apply together, `None` leaving the setting untouched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayProfile {
+    pub panel_overdrive: Option<PanelOverdrive>,
+    pub mini_led: Option<MiniLedMode>,
+}
+
+impl DisplayProfile {
+    /// Apply the profile. Settings are skipped when the hardware is not present.
+    pub fn apply(&self) -> Result<(), DisplayProfileError> {
+        if let Some(state) = self.panel_overdrive {
+            let panel_od = panel_od::get();
+            if panel_od.is_present()? {
+                panel_od.apply(state)?;
+            }
+        }
+
+        if let Some(mode) = self.mini_led {
+            match MiniLed::detect() {
+                Ok(mini_led) => mini_led.apply(mode)?,
+                Err(MiniLedError::NotFound) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Display profiles for each power source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayProfiles {
+    pub on_ac: DisplayProfile,
+    pub on_battery: DisplayProfile,
+}
+
+impl Default for DisplayProfiles {
+    /// Everything on while on AC, mini-LED and overdrive off on battery to save power.
+    fn default() -> Self {
+        DisplayProfiles {
+            on_ac: DisplayProfile {
+                panel_overdrive: Some(PanelOverdrive::On),
+                mini_led: Some(MiniLedMode::On),
+            },
+            on_battery: DisplayProfile {
+                panel_overdrive: Some(PanelOverdrive::Off),
+                mini_led: Some(MiniLedMode::Off),
+            },
+        }
+    }
+}
+
+impl DisplayProfiles {
+    pub const fn for_source(&self, source: PowerSource) -> &DisplayProfile {
+        match source {
+            PowerSource::Ac => &self.on_ac,
+            PowerSource::Battery => &self.on_battery,
+        }
+    }
+
+    /// Apply the profile of the current power source, returning the source.
+    pub fn apply_current(&self) -> Result<PowerSource, DisplayProfileError> {
+        let source = battery::power_source()?;
+        self.for_source(source).apply()?;
+        Ok(source)
+    }
+}
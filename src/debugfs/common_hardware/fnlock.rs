@@ -0,0 +1,57 @@
+//! ASUS_WMI_DEVID_FNLOCK
+//!
+//! Fn-lock, making the top row send the function keys without holding <kbd>Fn</kbd>.
+//! The firmware resets it at boot or after suspend, use [persistent] to store the
+//! desired state and re-apply it.
+
+use crate::{
+    auto_impl_config,
+    debugfs::{Config, Hardware},
+    error::StateError,
+    persist::Persistent,
+};
+
+pub const DEV_ID: u64 = 0x00100023;
+
+pub const fn get() -> Hardware<FnLockState> {
+    Hardware::new(DEV_ID)
+}
+
+/// Fn-lock with its desired state stored under [STATE_DIR](crate::persist::STATE_DIR).
+pub fn persistent() -> Persistent<FnLockState> {
+    Persistent::new("fnlock", get()).masked(0x1)
+}
+
+auto_impl_config!(FnLockState, u8, Off = 0, On = 1);
+
+#[test]
+fn fnlock() {
+    let fnlock = get();
+
+    let initial_state = fnlock
+        .read()
+        .expect("there should be a current state of fn-lock");
+
+    // turn on fn-lock
+    fnlock
+        .apply(FnLockState::On)
+        .expect("fn-lock should be turned on");
+    assert_eq!(fnlock.read().unwrap(), FnLockState::On);
+
+    // turn off fn-lock
+    fnlock
+        .apply(FnLockState::Off)
+        .expect("fn-lock should be turned off");
+    assert_eq!(fnlock.read().unwrap(), FnLockState::Off);
+
+    // return to initial state
+    fnlock
+        .apply(initial_state)
+        .expect("fn-lock should be switched to initial state");
+
+    assert_eq!(
+        fnlock.read().unwrap(),
+        initial_state,
+        "Failed to revert to initial state"
+    );
+}
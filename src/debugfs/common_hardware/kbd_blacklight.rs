@@ -2,9 +2,41 @@
 //!
 //! Asus Keyboard blacklight control with a given number of blacklight mode settings
 //! using [create_kbd_brightness_enum](crate::create_kbd_brightness_enum) utility macro.
+//!
+//! Alternatively, [KbdBacklight] discovers the brightness levels at runtime, and can go
+//! through the `asus::kbd_backlight` LED class device which doesn't require root when
+//! the permissions allow it.
+
+use crate::{
+    debugfs::{error::HardwareError, Hardware},
+    sysfs,
+    worker::Worker,
+};
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    ops::RangeInclusive,
+    os::fd::AsRawFd,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use thiserror::Error;
 
 pub const DEV_ID: u64 = 0x00050021;
 
+/// Brightness bits of the DSTS value.
+pub const BRIGHTNESS_MASK: u64 = 0x7F;
+
+/// Set along with the level when writing the brightness through debugfs.
+///
+/// <https://github.com/torvalds/linux/blob/3e5e6c9900c3d71895e8bdeacfb579462e98eba1/drivers/platform/x86/asus-wmi.c#L1544-L1549>
+pub const WRITE_BIT: u64 = 0x80;
+
+/// Number of levels above off, as set by the asus-wmi driver.
+const DEBUGFS_MAX_BRIGHTNESS: u8 = 3;
+
+const LED_PATH: &str = "/sys/class/leds/asus::kbd_backlight";
+
 /// Use this macro to create an enum for keyboard backlight
 ///
 /// Macro should always start with an off state `Off = 0` and the rest of the states can be defined as needed.
@@ -37,10 +69,12 @@ macro_rules! create_kbd_brightness_enum {
         impl TryFrom<u64> for $enum_name {
             type Error = StateError;
 
+            /// Accepts both the level read back from the hardware
+            /// and the `0x80 | level` value written to the hardware.
             fn try_from(value: u64) -> Result<Self, Self::Error> {
                 match value {
                     0 => Ok($enum_name::$off_state),
-                    $( $value => Ok($enum_name::$name), )*
+                    $( v if v == $value || v == $enum_name::$name as u64 => Ok($enum_name::$name), )*
                     _ => Err(StateError::NotPossibleState { value }),
                 }
             }
@@ -54,11 +88,257 @@ macro_rules! create_kbd_brightness_enum {
     };
 }
 
+#[derive(Debug, Error)]
+pub enum KbdBacklightError {
+    #[error("Keyboard backlight is not present on this machine!")]
+    NotFound,
+
+    #[error("Brightness `{level}` is out of range, the maximum is `{max}`!")]
+    OutOfRange { level: u8, max: u8 },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to write `{path:?}`! {error}")]
+    WriteFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
+
+/// Interface used to drive the keyboard backlight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KbdBacklightBackend {
+    /// `asus::kbd_backlight` LED class device.
+    LedClass(PathBuf),
+    /// DEV_ID through debugfs.
+    Debugfs,
+}
+
+/// Keyboard backlight with its brightness levels discovered at runtime.
+#[derive(Debug, Clone)]
+pub struct KbdBacklight {
+    backend: KbdBacklightBackend,
+    max: u8,
+}
+
+impl KbdBacklight {
+    /// Use the LED class device at the given path (example: `/sys/class/leds/asus::kbd_backlight`).
+    pub fn led_class(path: impl Into<PathBuf>) -> Result<Self, KbdBacklightError> {
+        let path = path.into();
+        let max = read_level(&path.join("max_brightness"))?;
+        Ok(KbdBacklight {
+            backend: KbdBacklightBackend::LedClass(path),
+            max,
+        })
+    }
+
+    /// Use the DEV_ID through debugfs, with the levels set by the asus-wmi driver.
+    pub const fn debugfs() -> Self {
+        KbdBacklight {
+            backend: KbdBacklightBackend::Debugfs,
+            max: DEBUGFS_MAX_BRIGHTNESS,
+        }
+    }
+
+    /// Prefer the LED class device, falling back to debugfs.
+    pub fn detect() -> Result<Self, KbdBacklightError> {
+        if Path::new(LED_PATH).exists() {
+            return Self::led_class(LED_PATH);
+        }
+
+        if Hardware::<u8>::new(DEV_ID).is_present()? {
+            Ok(Self::debugfs())
+        } else {
+            Err(KbdBacklightError::NotFound)
+        }
+    }
+
+    pub const fn backend(&self) -> &KbdBacklightBackend {
+        &self.backend
+    }
+
+    /// Highest brightness level.
+    pub const fn max(&self) -> u8 {
+        self.max
+    }
+
+    /// Available brightness levels, `0` being off.
+    pub const fn levels(&self) -> RangeInclusive<u8> {
+        0..=self.max
+    }
+
+    /// Read the current brightness level.
+    pub fn read(&self) -> Result<u8, KbdBacklightError> {
+        match &self.backend {
+            KbdBacklightBackend::LedClass(path) => read_level(&path.join("brightness")),
+            KbdBacklightBackend::Debugfs => {
+                Ok(Hardware::<u8>::new(DEV_ID).read_masked(BRIGHTNESS_MASK)?)
+            }
+        }
+    }
+
+    /// Apply the given brightness level.
+    pub fn set(&self, level: u8) -> Result<(), KbdBacklightError> {
+        if level > self.max {
+            return Err(KbdBacklightError::OutOfRange {
+                level,
+                max: self.max,
+            });
+        }
+
+        match &self.backend {
+            KbdBacklightBackend::LedClass(path) => {
+                let path = path.join("brightness");
+                sysfs::write(&path, level)
+                    .map_err(|error| KbdBacklightError::WriteFailed { path, error })
+            }
+            KbdBacklightBackend::Debugfs => {
+                let value = if level == 0 {
+                    0
+                } else {
+                    WRITE_BIT | u64::from(level)
+                };
+                Ok(Hardware::<u64>::new(DEV_ID).apply(value)?)
+            }
+        }
+    }
+
+    /// Switch to the level after the current one, wrapping to off, and return it.
+    pub fn cycle(&self) -> Result<u8, KbdBacklightError> {
+        let next = match self.read()? {
+            level if level >= self.max => 0,
+            level => level + 1,
+        };
+        self.set(next)?;
+        Ok(next)
+    }
+
+    /// Read the level last set by the firmware (such as with the <kbd>Fn</kbd> keys).
+    ///
+    /// Returns `None` if the firmware hasn't changed it since boot, or the backend
+    /// doesn't report it.
+    pub fn read_hw_changed(&self) -> Result<Option<u8>, KbdBacklightError> {
+        let KbdBacklightBackend::LedClass(path) = &self.backend else {
+            return Ok(None);
+        };
+        let path = path.join("brightness_hw_changed");
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        // the kernel reports ENODATA until the first change
+        Ok(read_level(&path).ok())
+    }
+
+    /// Call `on_change` with the new level whenever the firmware changes the brightness,
+    /// waiting for the kernel's notification on a background thread and checking for a
+    /// stop request every `interval`.
+    ///
+    /// Only the LED class backend reports these changes, the worker finishes right away
+    /// with the debugfs backend.
+    pub fn watch_hw_changed(
+        &self,
+        interval: Duration,
+        mut on_change: impl FnMut(u8) + Send + 'static,
+    ) -> Worker<Result<(), KbdBacklightError>> {
+        let path = match &self.backend {
+            KbdBacklightBackend::LedClass(path) => Some(path.join("brightness_hw_changed")),
+            KbdBacklightBackend::Debugfs => None,
+        };
+        Worker::spawn(move |signal| {
+            let Some(path) = path else {
+                return Ok(());
+            };
+            let read_failed = |error| KbdBacklightError::ReadFailed {
+                path: path.clone(),
+                error,
+            };
+            let mut file = File::open(&path).map_err(read_failed)?;
+            let timeout = libc::c_int::try_from(interval.as_millis()).unwrap_or(libc::c_int::MAX);
+
+            // sysfs only notifies pollers once the attribute has been read
+            read_hw_level(&mut file, &path)?;
+            while !signal.is_stopped() {
+                let mut pollfd = libc::pollfd {
+                    fd: file.as_raw_fd(),
+                    events: libc::POLLPRI | libc::POLLERR,
+                    revents: 0,
+                };
+                // SAFETY: `pollfd` is a single valid entry that outlives the call.
+                let ready = unsafe { libc::poll(&mut pollfd, 1, timeout) };
+                if ready < 0 {
+                    let error = io::Error::last_os_error();
+                    if error.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    return Err(read_failed(error));
+                }
+                if ready > 0 {
+                    if let Some(level) = read_hw_level(&mut file, &path)? {
+                        on_change(level);
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Re-read `brightness_hw_changed` from the start, which also re-arms the notification.
+///
+/// Returns `None` while the kernel reports ENODATA, before the first change.
+fn read_hw_level(file: &mut File, path: &Path) -> Result<Option<u8>, KbdBacklightError> {
+    let mut value = String::new();
+    let read = file
+        .seek(SeekFrom::Start(0))
+        .and_then(|_| file.read_to_string(&mut value));
+    match read {
+        Err(error) if error.raw_os_error() == Some(libc::ENODATA) => return Ok(None),
+        Err(error) => {
+            return Err(KbdBacklightError::ReadFailed {
+                path: path.to_path_buf(),
+                error,
+            })
+        }
+        Ok(_) => {}
+    }
+    let value = value.trim();
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| KbdBacklightError::UnexpectedValue {
+            path: path.to_path_buf(),
+            value: value.to_owned(),
+        })
+}
+
+fn read_level(path: &Path) -> Result<u8, KbdBacklightError> {
+    let value = sysfs::read_trimmed(path).map_err(|error| KbdBacklightError::ReadFailed {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    value
+        .parse()
+        .map_err(|_| KbdBacklightError::UnexpectedValue {
+            path: path.to_path_buf(),
+            value,
+        })
+}
+
 #[cfg(test)]
 mod test {
     #[test]
     fn kbd_backlight() {
-        use crate::create_kbd_brightness_enum;
         use crate::debugfs::common_hardware::kbd_blacklight::DEV_ID;
         use crate::debugfs::Hardware;
         use crate::debugfs::{error::StateError, Config};
@@ -114,4 +394,48 @@ mod test {
         assert_eq!(kbd_backlight.read().unwrap(), initial_state, "Initial state was not set!");
         println!("Success");
     }
+
+    #[test]
+    fn kbd_backlight_levels() {
+        use super::KbdBacklight;
+
+        let kbd_backlight = KbdBacklight::detect().expect("there should be a keyboard backlight");
+
+        let initial_level = kbd_backlight
+            .read()
+            .expect("there should be a current keyboard backlight level");
+
+        for level in kbd_backlight.levels() {
+            kbd_backlight
+                .set(level)
+                .expect("keyboard backlight should be set");
+            assert_eq!(kbd_backlight.read().unwrap(), level);
+        }
+
+        // return to initial level
+        kbd_backlight
+            .set(initial_level)
+            .expect("keyboard backlight should be switched to initial level");
+        assert_eq!(
+            kbd_backlight.read().unwrap(),
+            initial_level,
+            "Failed to revert to initial level"
+        );
+    }
+
+    #[test]
+    fn kbd_brightness_enum_values() {
+        use crate::debugfs::{error::StateError, Config};
+
+        create_kbd_brightness_enum!(KbdBrightness, Off = 0, Low = 1, High = 3);
+
+        assert_eq!(KbdBrightness::try_from(3).unwrap(), KbdBrightness::High);
+        assert_eq!(KbdBrightness::try_from(0x83).unwrap(), KbdBrightness::High);
+        assert_eq!(
+            KbdBrightness::try_from(KbdBrightness::Low as u64).unwrap(),
+            KbdBrightness::Low
+        );
+        assert!(KbdBrightness::try_from(2).is_err());
+        assert_eq!(KbdBrightness::High.to_config(), "131");
+    }
 }
@@ -0,0 +1,58 @@
+//! ASUS_WMI_DEVID_LIGHTBAR
+//!
+//! Lightbar LED found on the front or lid of some ROG and TUF models.
+
+use crate::{
+    auto_impl_config,
+    debugfs::{error::HardwareError, Config, Hardware},
+    error::StateError,
+    persist::Persistent,
+};
+
+pub const DEV_ID: u64 = 0x00050025;
+
+pub const fn get() -> Hardware<Lightbar> {
+    Hardware::new(DEV_ID)
+}
+
+/// Check the lightbar is present on this machine.
+pub fn is_present() -> Result<bool, HardwareError> {
+    get().is_present()
+}
+
+/// Lightbar with its state stored under [STATE_DIR](crate::persist::STATE_DIR).
+pub fn persistent() -> Persistent<Lightbar> {
+    Persistent::new("lightbar", get()).masked(0x1)
+}
+
+auto_impl_config!(Lightbar, u8, Off = 0, On = 1);
+
+#[test]
+fn lightbar() {
+    let lightbar = get();
+
+    let initial_state = lightbar
+        .read()
+        .expect("there should be a current state of lightbar");
+
+    lightbar
+        .apply(Lightbar::Off)
+        .expect("lightbar should be turned off");
+    assert_eq!(lightbar.read().unwrap(), Lightbar::Off);
+
+    lightbar
+        .apply(Lightbar::On)
+        .expect("lightbar should be turned on");
+    assert_eq!(lightbar.read().unwrap(), Lightbar::On);
+
+    // return to initial state
+    lightbar
+        .apply(initial_state)
+        .expect("lightbar should be switched to initial state");
+
+    assert_eq!(
+        lightbar.read().unwrap(),
+        initial_state,
+        "Failed to revert to initial state"
+    );
+}
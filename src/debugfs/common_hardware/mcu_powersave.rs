@@ -0,0 +1,58 @@
+//! ASUS_WMI_DEVID_MCU_POWERSAVE
+//!
+//! Powersave mode of the MCU, lowering the standby power draw on ROG Ally and laptops.
+
+use crate::{
+    auto_impl_config,
+    debugfs::{error::HardwareError, Config, Hardware},
+    error::StateError,
+    persist::Persistent,
+};
+
+pub const DEV_ID: u64 = 0x001200E2;
+
+pub const fn get() -> Hardware<McuPowersave> {
+    Hardware::new(DEV_ID)
+}
+
+/// Check the MCU powersave is present on this machine.
+pub fn is_present() -> Result<bool, HardwareError> {
+    get().is_present()
+}
+
+/// MCU powersave with its state stored under [STATE_DIR](crate::persist::STATE_DIR).
+pub fn persistent() -> Persistent<McuPowersave> {
+    Persistent::new("mcu_powersave", get()).masked(0x1)
+}
+
+auto_impl_config!(McuPowersave, u8, Off = 0, On = 1);
+
+#[test]
+fn mcu_powersave() {
+    let mcu_powersave = get();
+
+    let initial_state = mcu_powersave
+        .read()
+        .expect("there should be a current state of MCU powersave");
+
+    mcu_powersave
+        .apply(McuPowersave::Off)
+        .expect("MCU powersave should be turned off");
+    assert_eq!(mcu_powersave.read().unwrap(), McuPowersave::Off);
+
+    mcu_powersave
+        .apply(McuPowersave::On)
+        .expect("MCU powersave should be turned on");
+    assert_eq!(mcu_powersave.read().unwrap(), McuPowersave::On);
+
+    // return to initial state
+    mcu_powersave
+        .apply(initial_state)
+        .expect("MCU powersave should be switched to initial state");
+
+    assert_eq!(
+        mcu_powersave.read().unwrap(),
+        initial_state,
+        "Failed to revert to initial state"
+    );
+}
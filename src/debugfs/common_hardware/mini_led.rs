@@ -0,0 +1,193 @@
+//! ASUS_WMI_DEVID_MINI_LED_MODE and ASUS_WMI_DEVID_MINI_LED_MODE2
+//!
+//! Mini-LED backlight of ROG displays. The two generations use different values for the
+//! same modes, so they are mapped to a single [MiniLedMode].
+
+use crate::debugfs::{error::HardwareError, Hardware};
+use thiserror::Error;
+
+/// Bits of the DSTS value holding the mode.
+pub const MODE_MASK: u64 = 0x03;
+
+/// Normalized mini-LED mode, independent of the generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MiniLedMode {
+    Off,
+    /// Regular local dimming (called "weak" on the second generation).
+    On,
+    /// Only available on the second generation.
+    Strong,
+}
+
+/// Mini-LED generations, each with its own DEV_ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MiniLedGeneration {
+    /// ASUS_WMI_DEVID_MINI_LED_MODE, on/off only.
+    First,
+    /// ASUS_WMI_DEVID_MINI_LED_MODE2, with an additional strong mode.
+    Second,
+}
+
+impl MiniLedGeneration {
+    /// All the known generations, newest first.
+    pub const ALL: [MiniLedGeneration; 2] = [MiniLedGeneration::Second, MiniLedGeneration::First];
+
+    pub const fn dev_id(self) -> u64 {
+        match self {
+            MiniLedGeneration::First => 0x0005001E,
+            MiniLedGeneration::Second => 0x0005002E,
+        }
+    }
+
+    /// Modes supported by the generation, in the order they are cycled.
+    pub const fn modes(self) -> &'static [MiniLedMode] {
+        match self {
+            MiniLedGeneration::First => &[MiniLedMode::Off, MiniLedMode::On],
+            MiniLedGeneration::Second => &[MiniLedMode::Off, MiniLedMode::On, MiniLedMode::Strong],
+        }
+    }
+
+    /// Raw value to write for the given mode, if the generation supports it.
+    pub const fn to_raw(self, mode: MiniLedMode) -> Option<u8> {
+        match (self, mode) {
+            (MiniLedGeneration::First, MiniLedMode::Off) => Some(0),
+            (MiniLedGeneration::First, MiniLedMode::On) => Some(1),
+            (MiniLedGeneration::First, MiniLedMode::Strong) => None,
+            (MiniLedGeneration::Second, MiniLedMode::On) => Some(0),
+            (MiniLedGeneration::Second, MiniLedMode::Strong) => Some(1),
+            (MiniLedGeneration::Second, MiniLedMode::Off) => Some(2),
+        }
+    }
+
+    /// Mode corresponding to the raw value read from the generation.
+    pub fn from_raw(self, value: u64) -> Option<MiniLedMode> {
+        self.modes()
+            .iter()
+            .copied()
+            .find(|mode| self.to_raw(*mode).map(u64::from) == Some(value))
+    }
+
+    pub const fn get(self) -> Hardware<u8> {
+        Hardware::new(self.dev_id())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum MiniLedError {
+    #[error("Mini-LED is not present on this machine!")]
+    NotFound,
+
+    #[error("Mini-LED mode `{mode:?}` is not supported by the `{generation:?}` generation!")]
+    UnsupportedMode {
+        mode: MiniLedMode,
+        generation: MiniLedGeneration,
+    },
+
+    #[error(
+        "The value `{value:#x}` is not a known mini-LED mode of the `{generation:?}` generation!"
+    )]
+    UnknownValue {
+        value: u64,
+        generation: MiniLedGeneration,
+    },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
+
+/// Mini-LED backlight, bound to the generation detected on the machine.
+#[derive(Debug, Clone)]
+pub struct MiniLed {
+    generation: MiniLedGeneration,
+    hardware: Hardware<u8>,
+}
+
+impl MiniLed {
+    /// Use the given generation without probing for it.
+    pub const fn new(generation: MiniLedGeneration) -> Self {
+        MiniLed {
+            generation,
+            hardware: generation.get(),
+        }
+    }
+
+    /// Probe the machine for the mini-LED generation.
+    pub fn detect() -> Result<Self, MiniLedError> {
+        for generation in MiniLedGeneration::ALL {
+            if generation.get().is_present()? {
+                return Ok(Self::new(generation));
+            }
+        }
+        Err(MiniLedError::NotFound)
+    }
+
+    pub const fn generation(&self) -> MiniLedGeneration {
+        self.generation
+    }
+
+    pub const fn modes(&self) -> &'static [MiniLedMode] {
+        self.generation.modes()
+    }
+
+    /// Read the current mode.
+    pub fn read(&self) -> Result<MiniLedMode, MiniLedError> {
+        let value = u64::from(self.hardware.read_masked(MODE_MASK)?);
+        self.generation
+            .from_raw(value)
+            .ok_or(MiniLedError::UnknownValue {
+                value,
+                generation: self.generation,
+            })
+    }
+
+    /// Apply the given mode.
+    pub fn apply(&self, mode: MiniLedMode) -> Result<(), MiniLedError> {
+        let value = self
+            .generation
+            .to_raw(mode)
+            .ok_or(MiniLedError::UnsupportedMode {
+                mode,
+                generation: self.generation,
+            })?;
+        Ok(self.hardware.apply(value)?)
+    }
+}
+
+#[test]
+fn raw_mapping_roundtrip() {
+    for generation in MiniLedGeneration::ALL {
+        for mode in generation.modes() {
+            let raw = generation
+                .to_raw(*mode)
+                .expect("listed mode should be mapped");
+            assert_eq!(generation.from_raw(raw.into()), Some(*mode));
+        }
+    }
+}
+
+#[test]
+fn mini_led() {
+    let mini_led = MiniLed::detect().expect("there should be a mini-LED display");
+
+    let initial_mode = mini_led
+        .read()
+        .expect("there should be a current mini-LED mode");
+
+    for mode in mini_led.modes() {
+        mini_led
+            .apply(*mode)
+            .expect("mini-LED mode should be applied");
+        assert_eq!(mini_led.read().unwrap(), *mode);
+    }
+
+    // return to initial mode
+    mini_led
+        .apply(initial_mode)
+        .expect("mini-LED should be switched to initial mode");
+
+    assert_eq!(
+        mini_led.read().unwrap(),
+        initial_mode,
+        "Failed to revert to initial mode"
+    );
+}
@@ -1,8 +1,19 @@
 //! Some of the commmonly available hardware abstractions in
 //! ASUS Device, with its valid available/possible states.
 
+pub mod boot_sound;
 pub mod camera_led;
+pub mod charge_mode;
+pub mod display_profile;
 pub mod fan;
+pub mod fnlock;
 pub mod kbd_blacklight;
 pub mod led_state;
+pub mod lightbar;
+pub mod mcu_powersave;
 pub mod mic_led;
+pub mod mini_led;
+pub mod panel_od;
+pub mod thermal_policy;
+pub mod toggles;
+pub mod touchpad;
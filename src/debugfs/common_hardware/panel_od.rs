@@ -0,0 +1,49 @@
+//! ASUS_WMI_DEVID_PANEL_OD
+//!
+//! Panel overdrive, reducing the response time of the display on ROG models.
+
+use crate::{
+    auto_impl_config,
+    debugfs::{Config, Hardware},
+    error::StateError,
+};
+
+pub const DEV_ID: u64 = 0x00050019;
+
+pub const fn get() -> Hardware<PanelOverdrive> {
+    Hardware::new(DEV_ID)
+}
+
+auto_impl_config!(PanelOverdrive, u8, Off = 0, On = 1);
+
+#[test]
+fn panel_od() {
+    let panel_od = get();
+
+    let initial_state = panel_od
+        .read()
+        .expect("there should be a current state of panel overdrive");
+
+    // turn on overdrive
+    panel_od
+        .apply(PanelOverdrive::On)
+        .expect("panel overdrive should be turned on");
+    assert_eq!(panel_od.read().unwrap(), PanelOverdrive::On);
+
+    // turn off overdrive
+    panel_od
+        .apply(PanelOverdrive::Off)
+        .expect("panel overdrive should be turned off");
+    assert_eq!(panel_od.read().unwrap(), PanelOverdrive::Off);
+
+    // return to initial state
+    panel_od
+        .apply(initial_state)
+        .expect("panel overdrive should be switched to initial state");
+
+    assert_eq!(
+        panel_od.read().unwrap(),
+        initial_state,
+        "Failed to revert to initial state"
+    );
+}
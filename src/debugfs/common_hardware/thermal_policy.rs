@@ -0,0 +1,267 @@
+//! ASUS_WMI_DEVID_FAN_BOOST_MODE, ASUS_WMI_DEVID_THROTTLE_THERMAL_POLICY
+//! and ASUS_WMI_DEVID_THROTTLE_THERMAL_POLICY_VIVO
+//!
+//! Different ASUS models expose the thermal policy (fan mode) through different DEV_IDs,
+//! each with its own meaning of the values. This module probes which of them are present
+//! and maps them to a single [ThermalProfile], so the same code can cycle profiles on
+//! ROG, TUF and Vivobook machines alike.
+//!
+//! Refer [fan](super::fan) for the Vivobook variant with its raw states.
+use super::fan::FanMode;
+use crate::debugfs::{error::HardwareError, Hardware};
+use thiserror::Error;
+
+/// Normalized thermal profile, independent of the DEV_ID used by the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThermalProfile {
+    Balanced,
+    Quiet,
+    Performance,
+    /// Only available on the Vivobook variant.
+    FullSpeed,
+}
+
+impl From<FanMode> for ThermalProfile {
+    fn from(mode: FanMode) -> Self {
+        match mode {
+            FanMode::Standard => ThermalProfile::Balanced,
+            FanMode::Whispher => ThermalProfile::Quiet,
+            FanMode::Performace => ThermalProfile::Performance,
+            FanMode::FullSpeed => ThermalProfile::FullSpeed,
+        }
+    }
+}
+
+impl From<ThermalProfile> for FanMode {
+    fn from(profile: ThermalProfile) -> Self {
+        match profile {
+            ThermalProfile::Balanced => FanMode::Standard,
+            ThermalProfile::Quiet => FanMode::Whispher,
+            ThermalProfile::Performance => FanMode::Performace,
+            ThermalProfile::FullSpeed => FanMode::FullSpeed,
+        }
+    }
+}
+
+/// The DEV_ID variants a model may use for its thermal policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThermalPolicyDevice {
+    /// ASUS_WMI_DEVID_THROTTLE_THERMAL_POLICY (ROG/TUF).
+    ThrottleThermalPolicy,
+    /// ASUS_WMI_DEVID_THROTTLE_THERMAL_POLICY_VIVO (Vivobook).
+    ThrottleThermalPolicyVivo,
+    /// ASUS_WMI_DEVID_FAN_BOOST_MODE (older models).
+    FanBoostMode,
+}
+
+impl ThermalPolicyDevice {
+    /// All the known variants, in the order the asus-wmi driver prefers them.
+    pub const ALL: [ThermalPolicyDevice; 3] = [
+        ThermalPolicyDevice::ThrottleThermalPolicy,
+        ThermalPolicyDevice::ThrottleThermalPolicyVivo,
+        ThermalPolicyDevice::FanBoostMode,
+    ];
+
+    pub const fn dev_id(self) -> u64 {
+        match self {
+            ThermalPolicyDevice::ThrottleThermalPolicy => 0x00120075,
+            ThermalPolicyDevice::ThrottleThermalPolicyVivo => 0x00110019,
+            ThermalPolicyDevice::FanBoostMode => 0x00110018,
+        }
+    }
+
+    /// Profiles supported by the variant, in the order they are cycled.
+    pub const fn profiles(self) -> &'static [ThermalProfile] {
+        match self {
+            ThermalPolicyDevice::ThrottleThermalPolicyVivo => &[
+                ThermalProfile::Quiet,
+                ThermalProfile::Balanced,
+                ThermalProfile::Performance,
+                ThermalProfile::FullSpeed,
+            ],
+            _ => &[
+                ThermalProfile::Quiet,
+                ThermalProfile::Balanced,
+                ThermalProfile::Performance,
+            ],
+        }
+    }
+
+    /// Raw value to write for the given profile, if the variant supports it.
+    pub const fn to_raw(self, profile: ThermalProfile) -> Option<u8> {
+        match (self, profile) {
+            (_, ThermalProfile::Balanced) => Some(0),
+            (ThermalPolicyDevice::ThrottleThermalPolicyVivo, ThermalProfile::Quiet) => Some(1),
+            (ThermalPolicyDevice::ThrottleThermalPolicyVivo, ThermalProfile::Performance) => {
+                Some(2)
+            }
+            (ThermalPolicyDevice::ThrottleThermalPolicyVivo, ThermalProfile::FullSpeed) => Some(3),
+            (_, ThermalProfile::Performance) => Some(1),
+            (_, ThermalProfile::Quiet) => Some(2),
+            (_, ThermalProfile::FullSpeed) => None,
+        }
+    }
+
+    /// Profile corresponding to the raw value read from the variant.
+    pub fn from_raw(self, value: u64) -> Option<ThermalProfile> {
+        self.profiles()
+            .iter()
+            .copied()
+            .find(|profile| self.to_raw(*profile).map(u64::from) == Some(value))
+    }
+
+    pub const fn get(self) -> Hardware<u8> {
+        Hardware::new(self.dev_id())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ThermalPolicyError {
+    #[error("None of the known thermal policy DEV_IDs are present on this machine!")]
+    NotFound,
+
+    #[error("Thermal profile `{profile:?}` is not supported by `{device:?}`!")]
+    UnsupportedProfile {
+        profile: ThermalProfile,
+        device: ThermalPolicyDevice,
+    },
+
+    #[error("The value `{value:#x}` is not a known thermal profile of `{device:?}`!")]
+    UnknownValue {
+        value: u64,
+        device: ThermalPolicyDevice,
+    },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
+
+/// Thermal policy of the machine, bound to the DEV_ID variant detected on it.
+#[derive(Debug, Clone)]
+pub struct ThermalPolicy {
+    device: ThermalPolicyDevice,
+    hardware: Hardware<u8>,
+}
+
+impl ThermalPolicy {
+    /// Use the given variant without probing for it.
+    pub const fn new(device: ThermalPolicyDevice) -> Self {
+        ThermalPolicy {
+            device,
+            hardware: device.get(),
+        }
+    }
+
+    /// Probe the machine and use the first variant present.
+    pub fn detect() -> Result<Self, ThermalPolicyError> {
+        Self::available()?
+            .first()
+            .map(|device| Self::new(*device))
+            .ok_or(ThermalPolicyError::NotFound)
+    }
+
+    /// All the variants present on the machine, in the order of preference.
+    pub fn available() -> Result<Vec<ThermalPolicyDevice>, ThermalPolicyError> {
+        let mut devices = Vec::new();
+        for device in ThermalPolicyDevice::ALL {
+            if device.get().is_present()? {
+                devices.push(device);
+            }
+        }
+        Ok(devices)
+    }
+
+    pub const fn device(&self) -> ThermalPolicyDevice {
+        self.device
+    }
+
+    /// Profiles supported by the machine, in the order they are cycled.
+    pub const fn profiles(&self) -> &'static [ThermalProfile] {
+        self.device.profiles()
+    }
+
+    /// Read the current thermal profile.
+    pub fn read(&self) -> Result<ThermalProfile, ThermalPolicyError> {
+        let value = self.hardware.read()?;
+        self.device
+            .from_raw(value.into())
+            .ok_or(ThermalPolicyError::UnknownValue {
+                value: value.into(),
+                device: self.device,
+            })
+    }
+
+    /// Apply the given thermal profile.
+    pub fn apply(&self, profile: ThermalProfile) -> Result<(), ThermalPolicyError> {
+        let value = self
+            .device
+            .to_raw(profile)
+            .ok_or(ThermalPolicyError::UnsupportedProfile {
+                profile,
+                device: self.device,
+            })?;
+        Ok(self.hardware.apply(value)?)
+    }
+
+    /// Switch to the profile after the current one and return it.
+    ///
+    /// Falls back to [Balanced](ThermalProfile::Balanced) if the current profile is unknown.
+    pub fn cycle(&self) -> Result<ThermalProfile, ThermalPolicyError> {
+        let profiles = self.profiles();
+        let next = match self.read() {
+            Ok(current) => profiles
+                .iter()
+                .position(|profile| *profile == current)
+                .map(|i| profiles[(i + 1) % profiles.len()])
+                .unwrap_or(ThermalProfile::Balanced),
+            Err(ThermalPolicyError::UnknownValue { .. }) => ThermalProfile::Balanced,
+            Err(e) => return Err(e),
+        };
+        self.apply(next)?;
+        Ok(next)
+    }
+}
+
+#[test]
+fn raw_mapping_roundtrip() {
+    for device in ThermalPolicyDevice::ALL {
+        for profile in device.profiles() {
+            let raw = device
+                .to_raw(*profile)
+                .expect("listed profile should be mapped");
+            assert_eq!(device.from_raw(raw.into()), Some(*profile));
+        }
+    }
+
+    assert_eq!(
+        ThermalPolicyDevice::FanBoostMode.to_raw(ThermalProfile::FullSpeed),
+        None
+    );
+}
+
+#[test]
+fn thermal_policy() {
+    let policy = ThermalPolicy::detect().expect("there should be a thermal policy device");
+
+    let initial_profile = policy
+        .read()
+        .expect("there should be a current thermal profile");
+
+    for profile in policy.profiles() {
+        policy
+            .apply(*profile)
+            .expect("thermal profile should be applied");
+        assert_eq!(policy.read().unwrap(), *profile);
+    }
+
+    // return to initial profile
+    policy
+        .apply(initial_profile)
+        .expect("thermal policy should be switched to initial profile");
+
+    assert_eq!(
+        policy.read().unwrap(),
+        initial_profile,
+        "Failed to revert to initial profile"
+    );
+}
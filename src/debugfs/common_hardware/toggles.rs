@@ -0,0 +1,68 @@
+//! Snapshot and restore of the toggles reset by the firmware at boot or after suspend:
+//! [touchpad], [fnlock], [boot_sound], [mcu_powersave] and [lightbar].
+//!
+//! Run [Toggles::restore] from a boot-time or resume-time hook to bring back the states
+//! stored with [Toggles::snapshot] (or through each toggle's `persistent()`).
+
+use super::{
+    boot_sound::{self, BootSound},
+    fnlock::{self, FnLockState},
+    lightbar::{self, Lightbar},
+    mcu_powersave::{self, McuPowersave},
+    touchpad::{self, TouchpadState},
+};
+use crate::{
+    debugfs::Config,
+    persist::{PersistError, Persistent},
+};
+
+/// States of the toggles, `None` for the ones not present or not stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Toggles {
+    pub touchpad: Option<TouchpadState>,
+    pub fnlock: Option<FnLockState>,
+    pub boot_sound: Option<BootSound>,
+    pub mcu_powersave: Option<McuPowersave>,
+    pub lightbar: Option<Lightbar>,
+}
+
+impl Toggles {
+    /// Store the current state of every toggle present on the machine, returning them.
+    ///
+    /// The states are read without writing to the hardware.
+    pub fn snapshot() -> Result<Self, PersistError> {
+        Ok(Toggles {
+            touchpad: snapshot(touchpad::persistent())?,
+            fnlock: snapshot(fnlock::persistent())?,
+            boot_sound: snapshot(boot_sound::persistent())?,
+            mcu_powersave: snapshot(mcu_powersave::persistent())?,
+            lightbar: snapshot(lightbar::persistent())?,
+        })
+    }
+
+    /// Re-apply the stored state of every toggle present on the machine, returning the
+    /// ones restored.
+    pub fn restore() -> Result<Self, PersistError> {
+        Ok(Toggles {
+            touchpad: restore(touchpad::persistent())?,
+            fnlock: restore(fnlock::persistent())?,
+            boot_sound: restore(boot_sound::persistent())?,
+            mcu_powersave: restore(mcu_powersave::persistent())?,
+            lightbar: restore(lightbar::persistent())?,
+        })
+    }
+}
+
+fn snapshot<State: Config>(persistent: Persistent<State>) -> Result<Option<State>, PersistError> {
+    if !persistent.hardware().is_present()? {
+        return Ok(None);
+    }
+    persistent.snapshot().map(Some)
+}
+
+fn restore<State: Config>(persistent: Persistent<State>) -> Result<Option<State>, PersistError> {
+    if !persistent.hardware().is_present()? {
+        return Ok(None);
+    }
+    persistent.restore()
+}
@@ -0,0 +1,56 @@
+//! ASUS_WMI_DEVID_TOUCHPAD
+//!
+//! Touchpad toggle, which the firmware resets at boot or after suspend.
+//! Use [persistent] to store the desired state and re-apply it.
+
+use crate::{
+    auto_impl_config,
+    debugfs::{Config, Hardware},
+    error::StateError,
+    persist::Persistent,
+};
+
+pub const DEV_ID: u64 = 0x00100011;
+
+pub const fn get() -> Hardware<TouchpadState> {
+    Hardware::new(DEV_ID)
+}
+
+/// Touchpad with its desired state stored under [STATE_DIR](crate::persist::STATE_DIR).
+pub fn persistent() -> Persistent<TouchpadState> {
+    Persistent::new("touchpad", get()).masked(0x1)
+}
+
+auto_impl_config!(TouchpadState, u8, Disabled = 0, Enabled = 1);
+
+#[test]
+fn touchpad() {
+    let touchpad = get();
+
+    let initial_state = touchpad
+        .read()
+        .expect("there should be a current state of touchpad");
+
+    // disable touchpad
+    touchpad
+        .apply(TouchpadState::Disabled)
+        .expect("touchpad should be disabled");
+    assert_eq!(touchpad.read().unwrap(), TouchpadState::Disabled);
+
+    // enable touchpad
+    touchpad
+        .apply(TouchpadState::Enabled)
+        .expect("touchpad should be enabled");
+    assert_eq!(touchpad.read().unwrap(), TouchpadState::Enabled);
+
+    // return to initial state
+    touchpad
+        .apply(initial_state)
+        .expect("touchpad should be switched to initial state");
+
+    assert_eq!(
+        touchpad.read().unwrap(),
+        initial_state,
+        "Failed to revert to initial state"
+    );
+}
@@ -1,7 +1,12 @@
 //! Hardware abstraction to control the hardware configurations.
 
 use super::{error::*, Config};
-use std::{cell::Cell, fs, marker::PhantomData};
+use std::{
+    cell::Cell,
+    fs,
+    marker::PhantomData,
+    sync::{Mutex, MutexGuard},
+};
 
 /// Provides a safe interface to control the hardware configurations
 /// initialized with the valid state configuration enum of the hardware.
@@ -15,6 +20,25 @@ where
     pub(crate) safe_read_mask: Cell<Option<u64>>,
 }
 
+/// Bit set in the DSTS value when the device is present on the machine.
+///
+/// Refer `ASUS_WMI_DSTS_PRESENCE_BIT` in the
+/// [asus-wmi driver code](https://github.com/torvalds/linux/blob/master/include/linux/platform_data/x86/asus-wmi.h).
+pub const DSTS_PRESENCE_BIT: u64 = 0x00010000;
+
+/// `ENODEV`, reported by the driver when the firmware doesn't know the DEV_ID.
+const ENODEV: i32 = 19;
+
+/// Serializes the accesses to the debugfs files, which are global to the driver.
+///
+/// Each access writes `dev_id` (and `ctrl_param`) before reading the result, so two
+/// threads interleaving would apply or read the value of the other's DEV_ID.
+static DEBUGFS_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock() -> MutexGuard<'static, ()> {
+    DEBUGFS_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 macro_rules! path {
     ($x:expr) => {
         concat!("/sys/kernel/debug/asus-nb-wmi/", $x)
@@ -37,6 +61,26 @@ where
         }
     }
 
+    /// DEV_ID of the hardware.
+    pub const fn dev_id(&self) -> u64 {
+        self.dev_id
+    }
+
+    /// Check whether the hardware is present on this machine.
+    ///
+    /// Reads the DSTS of the hardware and looks for the presence bit, the same way
+    /// the asus-wmi driver decides whether to expose a device. A DEV_ID unknown to
+    /// the firmware is reported as absent instead of an error.
+    pub fn is_present(&self) -> Result<bool, HardwareError> {
+        match self.read_dsts() {
+            Ok(value) => Ok(value & DSTS_PRESENCE_BIT != 0),
+            Err(HardwareError::DstsConfigFileError(DstsConfigFileError::StateReadFailed {
+                ref error,
+            })) if error.raw_os_error() == Some(ENODEV) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Open the hardware config files.
     ///
     /// Used for making affect to any changes to the hardware by reading the hardware file.
@@ -81,6 +125,12 @@ where
     ///
     /// Use [apply](Hardware::apply) with a well defined state enum to ensure safety.
     fn _apply_raw(&self, ctrl_param: impl Config) -> Result<(), HardwareError> {
+        let _lock = lock();
+        self.apply_unlocked(ctrl_param)
+    }
+
+    /// [_apply_raw](Hardware::_apply_raw) for callers already holding the lock.
+    fn apply_unlocked(&self, ctrl_param: impl Config) -> Result<(), HardwareError> {
         self.open()?;
 
         fs::write(path!("ctrl_param"), ctrl_param.to_config())
@@ -102,17 +152,19 @@ where
     ///
     /// Relates to [read_stale](Hardware::read_stale) function which is not reliable.
     pub fn read(&self) -> Result<State, HardwareError> {
-        let current_raw_state = self.read_dsts()?;
+        // held across the whole mask discovery, so no other access sees the temporary 0
+        let _lock = lock();
+        let current_raw_state = self.read_dsts_unlocked()?;
 
         let mask = if let Some(mask) = self.safe_read_mask.get() {
             mask
         } else {
-            unsafe { self.apply_any(0_u64) }?;
-            let mask = self.read_dsts()?;
+            self.apply_unlocked(0_u64)?;
+            let mask = self.read_dsts_unlocked()?;
             self.safe_read_mask.set(Some(mask));
 
             // revert back to the original state
-            unsafe { self.apply_any(current_raw_state ^ mask) }?;
+            self.apply_unlocked(current_raw_state ^ mask)?;
 
             mask
         };
@@ -127,6 +179,19 @@ where
         })
     }
 
+    /// Read the current state of the hardware by masking its DSTS value.
+    ///
+    /// Unlike [read](Hardware::read), this never writes to the hardware. Use it for
+    /// hardware where temporarily applying `0` to find the mask is not acceptable
+    /// (such as the battery charge limit or the GPU MUX), with the mask of the state
+    /// bits as given in the
+    /// [asus-wmi driver code](https://github.com/torvalds/linux/blob/3e5e6c9900c3d71895e8bdeacfb579462e98eba1/include/linux/platform_data/x86/asus-wmi.h#L150-L158).
+    pub fn read_masked(&self, mask: u64) -> Result<State, HardwareError> {
+        let value = self.read_dsts()? & mask;
+
+        State::try_from(value).map_err(|_| StateError::NotPossibleState { value }.into())
+    }
+
     /// Read the raw value of the hardware config. This value is the actual state of the hardware,
     /// but cannot be directly mapped to the State enum without obtaining the default mask.
     ///
@@ -136,6 +201,12 @@ where
     ///
     /// **Usecase:** If you want to read the raw value of the hardware config, and then map it to the state yourself.
     pub fn read_dsts(&self) -> Result<u64, HardwareError> {
+        let _lock = lock();
+        self.read_dsts_unlocked()
+    }
+
+    /// [read_dsts](Hardware::read_dsts) for callers already holding the lock.
+    fn read_dsts_unlocked(&self) -> Result<u64, HardwareError> {
         self.open()?;
 
         let config = fs::read_to_string(path!("dsts"))
@@ -204,6 +275,7 @@ where
     ///
     /// [DSTS can be used to read the currect state accurately.](https://github.com/torvalds/linux/blob/3e5e6c9900c3d71895e8bdeacfb579462e98eba1/include/linux/platform_data/x86/asus-wmi.h#L150-L158)
     pub fn read_stale(&self) -> Result<Result<State, State>, HardwareError> {
+        let _lock = lock();
         self.open()?;
 
         let devs = fs::read_to_string(path!("devs"))
@@ -5,7 +5,7 @@
 
 
 mod config;
-pub use config::Hardware;
+pub use config::{Hardware, DSTS_PRESENCE_BIT};
 mod config_trait;
 pub mod error;
 pub use config_trait::Config;
@@ -0,0 +1,32 @@
+//! Error types and messages for the form_factor module.
+
+use crate::{
+    common_hardware::kbd_blacklight::KbdBacklightError, debugfs::error::HardwareError,
+    platform_profile::error::PlatformProfileError,
+};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FormFactorError {
+    #[error("Neither tablet mode nor the lid state is available on this machine!")]
+    NotSupported,
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error("{0}")]
+    KbdBacklightError(#[from] KbdBacklightError),
+
+    #[error("{0}")]
+    PlatformProfileError(#[from] PlatformProfileError),
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
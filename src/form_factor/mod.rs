@@ -0,0 +1,429 @@
+//! ASUS_WMI_DEVID_LID_FLIP and ASUS_WMI_DEVID_LID_FLIP_ROG
+//!
+//! Tablet mode of 2-in-1 models and the ACPI lid state, watched on a background
+//! [Worker] which reports a [FormFactorEvent] on every change. [Reactions] maps the
+//! events to changes of other hardware, restoring it once the event is undone.
+
+pub mod error;
+
+use crate::{
+    auto_impl_config,
+    common_hardware::{
+        kbd_blacklight::KbdBacklight,
+        thermal_policy::ThermalProfile,
+        touchpad::{self, TouchpadState},
+    },
+    debugfs::{Config, Hardware},
+    error::StateError,
+    platform_profile::ProfileController,
+    sysfs,
+    worker::{StopSignal, Worker},
+};
+use error::FormFactorError;
+use std::{fs, path::PathBuf, time::Duration};
+
+pub const LID_FLIP_DEV_ID: u64 = 0x00060062;
+pub const LID_FLIP_ROG_DEV_ID: u64 = 0x00060077;
+
+const LID_PATH: &str = "/proc/acpi/button/lid";
+
+auto_impl_config!(TabletMode, u8, Off = 0, On = 1);
+
+/// The DEV_ID variants a model may use to report tablet mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TabletModeDevice {
+    /// ASUS_WMI_DEVID_LID_FLIP
+    LidFlip,
+    /// ASUS_WMI_DEVID_LID_FLIP_ROG
+    LidFlipRog,
+}
+
+impl TabletModeDevice {
+    pub const ALL: [TabletModeDevice; 2] =
+        [TabletModeDevice::LidFlip, TabletModeDevice::LidFlipRog];
+
+    pub const fn dev_id(self) -> u64 {
+        match self {
+            TabletModeDevice::LidFlip => LID_FLIP_DEV_ID,
+            TabletModeDevice::LidFlipRog => LID_FLIP_ROG_DEV_ID,
+        }
+    }
+
+    pub const fn get(self) -> Hardware<TabletMode> {
+        Hardware::new(self.dev_id())
+    }
+
+    /// First variant present on this machine.
+    pub fn detect() -> Result<Option<Self>, FormFactorError> {
+        for device in Self::ALL {
+            if device.get().is_present()? {
+                return Ok(Some(device));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn read(self) -> Result<TabletMode, FormFactorError> {
+        Ok(self.get().read_masked(0x1)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LidState {
+    Open,
+    Closed,
+}
+
+/// Parse the `state:      open` line of `/proc/acpi/button/lid/*/state`.
+fn parse_lid_state(contents: &str) -> Option<LidState> {
+    match contents.trim().strip_prefix("state:")?.trim() {
+        "open" => Some(LidState::Open),
+        "closed" => Some(LidState::Closed),
+        _ => None,
+    }
+}
+
+/// Form factor related state, `None` where not available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormFactorState {
+    pub tablet_mode: Option<TabletMode>,
+    pub lid: Option<LidState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormFactorEvent {
+    TabletModeEntered,
+    TabletModeExited,
+    LidClosed,
+    LidOpened,
+}
+
+impl FormFactorState {
+    /// Laptop use with the lid open, where no [Trigger] is active.
+    pub const NEUTRAL: FormFactorState = FormFactorState {
+        tablet_mode: Some(TabletMode::Off),
+        lid: Some(LidState::Open),
+    };
+
+    /// Events leading from `previous` to this state.
+    pub fn events_since(&self, previous: &FormFactorState) -> Vec<FormFactorEvent> {
+        let mut events = Vec::new();
+
+        if let (Some(before), Some(now)) = (previous.tablet_mode, self.tablet_mode) {
+            if before != now {
+                events.push(match now {
+                    TabletMode::On => FormFactorEvent::TabletModeEntered,
+                    TabletMode::Off => FormFactorEvent::TabletModeExited,
+                });
+            }
+        }
+        if let (Some(before), Some(now)) = (previous.lid, self.lid) {
+            if before != now {
+                events.push(match now {
+                    LidState::Closed => FormFactorEvent::LidClosed,
+                    LidState::Open => FormFactorEvent::LidOpened,
+                });
+            }
+        }
+
+        events
+    }
+}
+
+/// Sources of the tablet mode and lid state found on this machine.
+#[derive(Debug, Clone)]
+pub struct FormFactor {
+    tablet_mode: Option<TabletModeDevice>,
+    lid: Option<PathBuf>,
+}
+
+impl FormFactor {
+    /// Find the tablet mode DEV_ID and the ACPI lid, failing if neither is available.
+    pub fn detect() -> Result<Self, FormFactorError> {
+        let tablet_mode = TabletModeDevice::detect()?;
+        let lid = fs::read_dir(LID_PATH).ok().and_then(|entries| {
+            let mut lids: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path().join("state"))
+                .filter(|path| path.exists())
+                .collect();
+            lids.sort();
+            lids.into_iter().next()
+        });
+
+        if tablet_mode.is_none() && lid.is_none() {
+            return Err(FormFactorError::NotSupported);
+        }
+        Ok(FormFactor { tablet_mode, lid })
+    }
+
+    pub const fn tablet_mode_device(&self) -> Option<TabletModeDevice> {
+        self.tablet_mode
+    }
+
+    pub fn read_lid(&self) -> Result<Option<LidState>, FormFactorError> {
+        let Some(path) = &self.lid else {
+            return Ok(None);
+        };
+
+        let value = sysfs::read_trimmed(path).map_err(|error| FormFactorError::ReadFailed {
+            path: path.clone(),
+            error,
+        })?;
+        parse_lid_state(&value)
+            .map(Some)
+            .ok_or_else(|| FormFactorError::UnexpectedValue {
+                path: path.clone(),
+                value,
+            })
+    }
+
+    pub fn read(&self) -> Result<FormFactorState, FormFactorError> {
+        Ok(FormFactorState {
+            tablet_mode: self.tablet_mode.map(|device| device.read()).transpose()?,
+            lid: self.read_lid()?,
+        })
+    }
+
+    /// Poll the state every `interval` until stopped, calling `on_event` for every
+    /// change and stopping at its first error.
+    ///
+    /// The first read is compared against `baseline`, or only taken as the baseline if
+    /// there is none.
+    fn run(
+        &self,
+        interval: Duration,
+        signal: &StopSignal,
+        baseline: Option<FormFactorState>,
+        mut on_event: impl FnMut(FormFactorEvent) -> Result<(), FormFactorError>,
+    ) -> Result<(), FormFactorError> {
+        let mut last = baseline;
+        loop {
+            let state = self.read()?;
+            if let Some(last) = &last {
+                for event in state.events_since(last) {
+                    on_event(event)?;
+                }
+            }
+            last = Some(state);
+
+            if signal.sleep(interval) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Poll the state every `interval` on a background thread, calling `on_event`
+    /// for every change.
+    pub fn watch(
+        self,
+        interval: Duration,
+        mut on_event: impl FnMut(FormFactorEvent) + Send + 'static,
+    ) -> Worker<Result<(), FormFactorError>> {
+        Worker::spawn(move |signal| {
+            self.run(interval, &signal, None, |event| {
+                on_event(event);
+                Ok(())
+            })
+        })
+    }
+
+    /// Run the given reactions on every event, on a background thread.
+    ///
+    /// The state is first compared against [NEUTRAL](FormFactorState::NEUTRAL), so the
+    /// reactions already apply when starting in tablet mode or with the lid closed.
+    /// Active reactions are restored whenever the worker finishes, whether stopped
+    /// or failed.
+    pub fn react(
+        self,
+        interval: Duration,
+        mut reactions: Reactions,
+    ) -> Worker<Result<(), FormFactorError>> {
+        Worker::spawn(move |signal| {
+            let result = self.run(interval, &signal, Some(FormFactorState::NEUTRAL), |event| {
+                reactions.handle(event)
+            });
+            let restored = reactions.restore_all();
+            result.and(restored)
+        })
+    }
+}
+
+/// Condition under which a [Reaction] is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    TabletMode,
+    LidClosed,
+}
+
+impl Trigger {
+    /// Whether the event starts (`Some(true)`) or ends (`Some(false)`) the trigger.
+    fn activated_by(self, event: FormFactorEvent) -> Option<bool> {
+        match (self, event) {
+            (Trigger::TabletMode, FormFactorEvent::TabletModeEntered) => Some(true),
+            (Trigger::TabletMode, FormFactorEvent::TabletModeExited) => Some(false),
+            (Trigger::LidClosed, FormFactorEvent::LidClosed) => Some(true),
+            (Trigger::LidClosed, FormFactorEvent::LidOpened) => Some(false),
+            _ => None,
+        }
+    }
+}
+
+/// Built-in hardware change applied while its [Trigger] is active.
+#[derive(Debug, Clone)]
+pub enum Reaction {
+    /// Turn the keyboard backlight off.
+    KbdBacklightOff(KbdBacklight),
+    /// Switch to the quiet thermal profile.
+    QuietFan(ProfileController),
+    /// Disable the touchpad.
+    DisableTouchpad,
+}
+
+/// State replaced by a reaction, restored when its trigger ends.
+#[derive(Debug, Clone, Copy)]
+enum Saved {
+    KbdLevel(u8),
+    Profile(ThermalProfile),
+    Touchpad(TouchpadState),
+}
+
+impl Reaction {
+    fn activate(&self) -> Result<Saved, FormFactorError> {
+        Ok(match self {
+            Reaction::KbdBacklightOff(backlight) => {
+                let level = backlight.read()?;
+                backlight.set(0)?;
+                Saved::KbdLevel(level)
+            }
+            Reaction::QuietFan(controller) => {
+                let profile = controller.read()?;
+                controller.apply(ThermalProfile::Quiet)?;
+                Saved::Profile(profile)
+            }
+            Reaction::DisableTouchpad => {
+                let touchpad = touchpad::get();
+                let state = touchpad.read()?;
+                touchpad.apply(TouchpadState::Disabled)?;
+                Saved::Touchpad(state)
+            }
+        })
+    }
+
+    fn restore(&self, saved: Saved) -> Result<(), FormFactorError> {
+        match (self, saved) {
+            (Reaction::KbdBacklightOff(backlight), Saved::KbdLevel(level)) => {
+                backlight.set(level)?
+            }
+            (Reaction::QuietFan(controller), Saved::Profile(profile)) => {
+                controller.apply(profile)?
+            }
+            (Reaction::DisableTouchpad, Saved::Touchpad(state)) => touchpad::get().apply(state)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Set of reactions to form factor events.
+///
+/// ```no_run
+/// use meh_asus::common_hardware::kbd_blacklight::KbdBacklight;
+/// use meh_asus::form_factor::{FormFactor, Reaction, Reactions, Trigger};
+/// use std::time::Duration;
+///
+/// let reactions = Reactions::new()
+///     .on(Trigger::TabletMode, Reaction::KbdBacklightOff(KbdBacklight::detect().unwrap()))
+///     .on(Trigger::TabletMode, Reaction::DisableTouchpad);
+///
+/// let worker = FormFactor::detect().unwrap().react(Duration::from_millis(500), reactions);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Reactions {
+    rules: Vec<(Trigger, Reaction, Option<Saved>)>,
+}
+
+impl Reactions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `reaction` while `trigger` is active.
+    pub fn on(mut self, trigger: Trigger, reaction: Reaction) -> Self {
+        self.rules.push((trigger, reaction, None));
+        self
+    }
+
+    /// Activate or restore the reactions matching the event.
+    pub fn handle(&mut self, event: FormFactorEvent) -> Result<(), FormFactorError> {
+        for (trigger, reaction, saved) in &mut self.rules {
+            match trigger.activated_by(event) {
+                Some(true) if saved.is_none() => *saved = Some(reaction.activate()?),
+                Some(false) => {
+                    if let Some(state) = saved.take() {
+                        reaction.restore(state)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Restore every active reaction.
+    pub fn restore_all(&mut self) -> Result<(), FormFactorError> {
+        for (_, reaction, saved) in &mut self.rules {
+            if let Some(state) = saved.take() {
+                reaction.restore(state)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn lid_state_parsing() {
+    assert_eq!(parse_lid_state("state:      open\n"), Some(LidState::Open));
+    assert_eq!(
+        parse_lid_state("state:      closed"),
+        Some(LidState::Closed)
+    );
+    assert_eq!(parse_lid_state("open"), None);
+}
+
+#[test]
+fn form_factor_events() {
+    let laptop = FormFactorState {
+        tablet_mode: Some(TabletMode::Off),
+        lid: Some(LidState::Open),
+    };
+    let tablet = FormFactorState {
+        tablet_mode: Some(TabletMode::On),
+        lid: Some(LidState::Closed),
+    };
+
+    assert!(laptop.events_since(&laptop).is_empty());
+    assert_eq!(
+        tablet.events_since(&laptop),
+        [
+            FormFactorEvent::TabletModeEntered,
+            FormFactorEvent::LidClosed
+        ]
+    );
+    assert_eq!(
+        laptop.events_since(&tablet),
+        [
+            FormFactorEvent::TabletModeExited,
+            FormFactorEvent::LidOpened
+        ]
+    );
+
+    let no_lid = FormFactorState {
+        lid: None,
+        ..laptop
+    };
+    assert_eq!(
+        tablet.events_since(&no_lid),
+        [FormFactorEvent::TabletModeEntered]
+    );
+}
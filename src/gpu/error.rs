@@ -0,0 +1,36 @@
+//! Error types and messages for the gpu module.
+
+use super::{GpuMode, GpuStep};
+use crate::debugfs::error::HardwareError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GpuError {
+    #[error("GPU mode `{mode:?}` is not supported on this machine!")]
+    UnsupportedMode { mode: GpuMode },
+
+    #[error("eGPU is not connected!")]
+    EgpuNotConnected,
+
+    #[error("dGPU `{device:?}` is in use, switching now may hang the machine! Close the applications using it first.")]
+    DgpuInUse { device: PathBuf },
+
+    #[error("A GPU mode switch to `{mode:?}` is already pending, reboot first!")]
+    RebootPending { mode: GpuMode },
+
+    #[error("Failed to apply `{step:?}`! {error}")]
+    StepFailed { step: GpuStep, error: HardwareError },
+
+    #[error("Failed to record the pending GPU mode! {error}")]
+    PendingWriteFailed { error: std::io::Error },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
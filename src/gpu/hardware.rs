@@ -0,0 +1,44 @@
+//! DEV_IDs and states of the GPU related hardware.
+//!
+//! The states are read with [read_masked](Hardware::read_masked) on the status bit,
+//! as writing `0` to find the mask would flip the MUX or the dGPU.
+
+use crate::{
+    auto_impl_config,
+    debugfs::{Config, Hardware},
+    error::StateError,
+};
+
+/// `ASUS_WMI_DSTS_STATUS_BIT`
+pub const STATUS_BIT: u64 = 0x1;
+
+/// ASUS_WMI_DEVID_DGPU
+pub const DGPU_DEV_ID: u64 = 0x00090020;
+/// ASUS_WMI_DEVID_EGPU
+pub const EGPU_DEV_ID: u64 = 0x00090019;
+/// ASUS_WMI_DEVID_EGPU_CONNECTED
+pub const EGPU_CONNECTED_DEV_ID: u64 = 0x00090018;
+/// ASUS_WMI_DEVID_GPU_MUX
+pub const GPU_MUX_DEV_ID: u64 = 0x00090016;
+
+auto_impl_config!(DgpuState, u8, Enabled = 0, Disabled = 1);
+auto_impl_config!(EgpuState, u8, Disabled = 0, Enabled = 1);
+auto_impl_config!(EgpuConnection, u8, Disconnected = 0, Connected = 1);
+auto_impl_config!(MuxMode, u8, Discrete = 0, Optimus = 1);
+
+pub const fn dgpu() -> Hardware<DgpuState> {
+    Hardware::new(DGPU_DEV_ID)
+}
+
+pub const fn egpu() -> Hardware<EgpuState> {
+    Hardware::new(EGPU_DEV_ID)
+}
+
+/// Read only, reports whether the eGPU is plugged in.
+pub const fn egpu_connected() -> Hardware<EgpuConnection> {
+    Hardware::new(EGPU_CONNECTED_DEV_ID)
+}
+
+pub const fn gpu_mux() -> Hardware<MuxMode> {
+    Hardware::new(GPU_MUX_DEV_ID)
+}
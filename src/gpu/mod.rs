@@ -0,0 +1,395 @@
+//! GPU mode manager over the dGPU disable, eGPU and MUX switch.
+//!
+//! Applying these in the wrong order, or while the dGPU is in use, can hang the
+//! machine. [GpuManager] plans the switch between [GpuMode]s as ordered [GpuStep]s,
+//! checks the preconditions before each of them, and keeps track of the MUX switch
+//! which only takes effect after a reboot.
+//!
+//! A pending switch is recorded under [STATE_DIR] along with the current boot id,
+//! call [GpuManager::resume_pending] on boot to finish the steps left for after reboot.
+
+pub mod error;
+pub mod hardware;
+mod pci;
+
+pub use pci::{DiscreteGpu, PowerState};
+
+use crate::{debugfs::Hardware, persist::STATE_DIR, sysfs};
+use error::GpuError;
+use hardware::*;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// File under [STATE_DIR] recording a switch waiting for a reboot.
+const PENDING_FILE: &str = "gpu_pending";
+const BOOT_ID_PATH: &str = "/proc/sys/kernel/random/boot_id";
+
+/// GPU modes of the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GpuMode {
+    /// dGPU disabled, integrated GPU only.
+    Integrated,
+    /// Integrated GPU driving the display, dGPU available for offloading.
+    Hybrid,
+    /// MUX switched to the dGPU driving the display directly.
+    Ultimate,
+    /// External GPU enabled.
+    Egpu,
+}
+
+impl GpuMode {
+    const fn as_str(self) -> &'static str {
+        match self {
+            GpuMode::Integrated => "integrated",
+            GpuMode::Hybrid => "hybrid",
+            GpuMode::Ultimate => "ultimate",
+            GpuMode::Egpu => "egpu",
+        }
+    }
+
+    fn from_str(s: &str) -> Option<Self> {
+        [
+            GpuMode::Integrated,
+            GpuMode::Hybrid,
+            GpuMode::Ultimate,
+            GpuMode::Egpu,
+        ]
+        .into_iter()
+        .find(|mode| mode.as_str() == s)
+    }
+}
+
+/// A single hardware change of a mode switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuStep {
+    Egpu(EgpuState),
+    Dgpu(DgpuState),
+    /// Takes effect only after reboot.
+    Mux(MuxMode),
+}
+
+impl GpuStep {
+    pub const fn requires_reboot(self) -> bool {
+        matches!(self, GpuStep::Mux(_))
+    }
+}
+
+/// Firmware state of the GPU hardware, `None` for the hardware not present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GpuState {
+    pub dgpu: Option<DgpuState>,
+    pub egpu: Option<EgpuState>,
+    pub mux: Option<MuxMode>,
+}
+
+impl GpuState {
+    /// Mode the firmware is set to, which for the MUX may only take effect after reboot.
+    pub fn mode(&self) -> GpuMode {
+        if self.egpu == Some(EgpuState::Enabled) {
+            GpuMode::Egpu
+        } else if self.mux == Some(MuxMode::Discrete) {
+            GpuMode::Ultimate
+        } else if self.dgpu == Some(DgpuState::Disabled) {
+            GpuMode::Integrated
+        } else {
+            GpuMode::Hybrid
+        }
+    }
+
+    /// Ordered steps to switch to the target mode.
+    ///
+    /// The eGPU is always released first, the dGPU is enabled before switching the MUX to
+    /// it, and the MUX is switched back to Optimus before the dGPU is disabled. Steps after
+    /// a [Mux](GpuStep::Mux) step are meant to be applied after reboot.
+    pub fn plan(&self, target: GpuMode) -> Vec<GpuStep> {
+        let mut steps = Vec::new();
+
+        if target != GpuMode::Egpu && self.egpu == Some(EgpuState::Enabled) {
+            steps.push(GpuStep::Egpu(EgpuState::Disabled));
+        }
+
+        match target {
+            GpuMode::Ultimate => {
+                if self.dgpu == Some(DgpuState::Disabled) {
+                    steps.push(GpuStep::Dgpu(DgpuState::Enabled));
+                }
+                if self.mux != Some(MuxMode::Discrete) {
+                    steps.push(GpuStep::Mux(MuxMode::Discrete));
+                }
+            }
+            _ => {
+                if self.mux == Some(MuxMode::Discrete) {
+                    steps.push(GpuStep::Mux(MuxMode::Optimus));
+                }
+                match target {
+                    GpuMode::Integrated if self.dgpu != Some(DgpuState::Disabled) => {
+                        steps.push(GpuStep::Dgpu(DgpuState::Disabled))
+                    }
+                    GpuMode::Hybrid if self.dgpu == Some(DgpuState::Disabled) => {
+                        steps.push(GpuStep::Dgpu(DgpuState::Enabled))
+                    }
+                    GpuMode::Egpu if self.egpu != Some(EgpuState::Enabled) => {
+                        steps.push(GpuStep::Egpu(EgpuState::Enabled))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        steps
+    }
+}
+
+/// Result of [GpuManager::switch].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwitchOutcome {
+    /// The mode is in effect.
+    Applied,
+    /// A reboot is needed, after which the remaining steps are applied by
+    /// [GpuManager::resume_pending].
+    RebootRequired { remaining: Vec<GpuStep> },
+}
+
+/// Manages the GPU mode, through the GPU hardware present on the machine.
+#[derive(Debug, Clone)]
+pub struct GpuManager {
+    dgpu: Option<Hardware<DgpuState>>,
+    egpu: Option<Hardware<EgpuState>>,
+    egpu_connected: Option<Hardware<EgpuConnection>>,
+    mux: Option<Hardware<MuxMode>>,
+}
+
+fn if_present<T: crate::Config>(hardware: Hardware<T>) -> Result<Option<Hardware<T>>, GpuError> {
+    Ok(hardware.is_present()?.then_some(hardware))
+}
+
+impl GpuManager {
+    /// Probe the GPU hardware present on the machine.
+    pub fn detect() -> Result<Self, GpuError> {
+        Ok(GpuManager {
+            dgpu: if_present(dgpu())?,
+            egpu: if_present(egpu())?,
+            egpu_connected: if_present(egpu_connected())?,
+            mux: if_present(gpu_mux())?,
+        })
+    }
+
+    /// Modes which can be switched to on this machine.
+    pub fn supported_modes(&self) -> Vec<GpuMode> {
+        let mut modes = vec![GpuMode::Hybrid];
+        if self.dgpu.is_some() {
+            modes.push(GpuMode::Integrated);
+        }
+        if self.mux.is_some() {
+            modes.push(GpuMode::Ultimate);
+        }
+        if self.egpu.is_some() {
+            modes.push(GpuMode::Egpu);
+        }
+        modes
+    }
+
+    /// Read the firmware state of the GPU hardware.
+    pub fn state(&self) -> Result<GpuState, GpuError> {
+        Ok(GpuState {
+            dgpu: self
+                .dgpu
+                .as_ref()
+                .map(|h| h.read_masked(STATUS_BIT))
+                .transpose()?,
+            egpu: self
+                .egpu
+                .as_ref()
+                .map(|h| h.read_masked(STATUS_BIT))
+                .transpose()?,
+            mux: self
+                .mux
+                .as_ref()
+                .map(|h| h.read_masked(STATUS_BIT))
+                .transpose()?,
+        })
+    }
+
+    /// Mode the firmware is set to. Check [pending](GpuManager::pending) to know
+    /// whether it is already in effect.
+    pub fn mode(&self) -> Result<GpuMode, GpuError> {
+        Ok(self.state()?.mode())
+    }
+
+    /// Whether the eGPU is plugged in.
+    pub fn egpu_connected(&self) -> Result<bool, GpuError> {
+        match &self.egpu_connected {
+            Some(h) => Ok(h.read_masked(STATUS_BIT)? == EgpuConnection::Connected),
+            None => Ok(false),
+        }
+    }
+
+    /// Mode waiting for a reboot to take effect, if any.
+    pub fn pending(&self) -> Result<Option<GpuMode>, GpuError> {
+        let Some((boot_id, mode)) = read_pending()? else {
+            return Ok(None);
+        };
+        Ok((boot_id == current_boot_id()?).then_some(mode))
+    }
+
+    /// Switch to the target mode.
+    ///
+    /// Refuses to touch the dGPU or MUX while the dGPU is powered up, unless `force` is set.
+    /// Steps after a MUX switch are left for [resume_pending](GpuManager::resume_pending).
+    pub fn switch(&self, target: GpuMode, force: bool) -> Result<SwitchOutcome, GpuError> {
+        if !self.supported_modes().contains(&target) {
+            return Err(GpuError::UnsupportedMode { mode: target });
+        }
+        if let Some(mode) = self.pending()? {
+            return Err(GpuError::RebootPending { mode });
+        }
+        if target == GpuMode::Egpu && !self.egpu_connected()? {
+            return Err(GpuError::EgpuNotConnected);
+        }
+
+        let steps = self.state()?.plan(target);
+        // checked once up front, as the plan itself may power up the dGPU midway
+        if !force && steps.iter().any(|step| !matches!(step, GpuStep::Egpu(_))) {
+            ensure_dgpu_idle()?;
+        }
+
+        for (i, step) in steps.iter().enumerate() {
+            self.apply_step(*step)?;
+
+            if step.requires_reboot() {
+                write_pending(target)?;
+                return Ok(SwitchOutcome::RebootRequired {
+                    remaining: steps[i + 1..].to_vec(),
+                });
+            }
+        }
+
+        clear_pending()?;
+        Ok(SwitchOutcome::Applied)
+    }
+
+    /// Finish a switch recorded before the last reboot, returning the mode switched to.
+    ///
+    /// Does nothing if no switch is recorded or the machine hasn't been rebooted since.
+    pub fn resume_pending(&self, force: bool) -> Result<Option<GpuMode>, GpuError> {
+        let Some((boot_id, mode)) = read_pending()? else {
+            return Ok(None);
+        };
+        if boot_id == current_boot_id()? {
+            return Ok(None);
+        }
+
+        // switch clears the record once applied, and rewrites it if another reboot is needed
+        self.switch(mode, force)?;
+        Ok(Some(mode))
+    }
+
+    fn apply_step(&self, step: GpuStep) -> Result<(), GpuError> {
+        let result = match step {
+            GpuStep::Egpu(state) => self.egpu.as_ref().map(|h| h.apply(state)),
+            GpuStep::Dgpu(state) => self.dgpu.as_ref().map(|h| h.apply(state)),
+            GpuStep::Mux(mode) => self.mux.as_ref().map(|h| h.apply(mode)),
+        };
+
+        match result {
+            Some(Err(error)) => Err(GpuError::StepFailed { step, error }),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn ensure_dgpu_idle() -> Result<(), GpuError> {
+    match DiscreteGpu::find_all()?
+        .into_iter()
+        .find(|gpu| gpu.power_state() == PowerState::Active)
+    {
+        Some(gpu) => Err(GpuError::DgpuInUse {
+            device: gpu.path().to_path_buf(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn pending_path() -> PathBuf {
+    Path::new(STATE_DIR).join(PENDING_FILE)
+}
+
+fn current_boot_id() -> Result<String, GpuError> {
+    sysfs::read_trimmed(BOOT_ID_PATH).map_err(|error| GpuError::ReadFailed {
+        path: BOOT_ID_PATH.into(),
+        error,
+    })
+}
+
+fn read_pending() -> Result<Option<(String, GpuMode)>, GpuError> {
+    let path = pending_path();
+    match sysfs::read_trimmed(&path) {
+        Ok(content) => Ok(content
+            .split_once(' ')
+            .and_then(|(boot_id, mode)| Some((boot_id.to_string(), GpuMode::from_str(mode)?)))),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(GpuError::ReadFailed { path, error }),
+    }
+}
+
+fn write_pending(mode: GpuMode) -> Result<(), GpuError> {
+    let content = format!("{} {}", current_boot_id()?, mode.as_str());
+    fs::create_dir_all(STATE_DIR)
+        .and_then(|_| sysfs::write(pending_path(), content))
+        .map_err(|error| GpuError::PendingWriteFailed { error })
+}
+
+fn clear_pending() -> Result<(), GpuError> {
+    match fs::remove_file(pending_path()) {
+        Err(error) if error.kind() != ErrorKind::NotFound => {
+            Err(GpuError::PendingWriteFailed { error })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[test]
+fn switch_plan_order() {
+    let ultimate = GpuState {
+        dgpu: Some(DgpuState::Enabled),
+        egpu: Some(EgpuState::Disabled),
+        mux: Some(MuxMode::Discrete),
+    };
+    assert_eq!(ultimate.mode(), GpuMode::Ultimate);
+    assert_eq!(
+        ultimate.plan(GpuMode::Integrated),
+        [
+            GpuStep::Mux(MuxMode::Optimus),
+            GpuStep::Dgpu(DgpuState::Disabled)
+        ]
+    );
+
+    let integrated = GpuState {
+        dgpu: Some(DgpuState::Disabled),
+        egpu: None,
+        mux: Some(MuxMode::Optimus),
+    };
+    assert_eq!(integrated.mode(), GpuMode::Integrated);
+    assert_eq!(
+        integrated.plan(GpuMode::Ultimate),
+        [
+            GpuStep::Dgpu(DgpuState::Enabled),
+            GpuStep::Mux(MuxMode::Discrete)
+        ]
+    );
+    assert!(integrated.plan(GpuMode::Integrated).is_empty());
+
+    let egpu = GpuState {
+        dgpu: Some(DgpuState::Enabled),
+        egpu: Some(EgpuState::Enabled),
+        mux: Some(MuxMode::Optimus),
+    };
+    assert_eq!(egpu.mode(), GpuMode::Egpu);
+    assert_eq!(
+        egpu.plan(GpuMode::Hybrid),
+        [GpuStep::Egpu(EgpuState::Disabled)]
+    );
+}
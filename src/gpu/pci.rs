@@ -0,0 +1,101 @@
+//! Discovery of the discrete GPU on the PCI bus and its runtime power state.
+
+use super::error::GpuError;
+use crate::sysfs;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const PCI_DEVICES_PATH: &str = "/sys/bus/pci/devices";
+const CPUINFO_PATH: &str = "/proc/cpuinfo";
+
+/// PCI vendor ids of the integrated GPUs, by CPU vendor.
+const INTEGRATED_VENDORS: [(&str, &str); 2] =
+    [("GenuineIntel", "0x8086"), ("AuthenticAMD", "0x1002")];
+
+/// Runtime power state of a PCI device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerState {
+    /// Powered up (`D0`), the device may be in use.
+    Active,
+    /// Suspended (`D3hot`/`D3cold`).
+    Suspended,
+    Unknown,
+}
+
+/// A discrete display controller on the PCI bus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscreteGpu {
+    path: PathBuf,
+}
+
+impl DiscreteGpu {
+    /// All the display controllers which are not the integrated GPU.
+    ///
+    /// The integrated GPU is told apart by being made by the CPU vendor, as the dGPU is the
+    /// boot VGA device once the MUX switches the display to it. Machines pairing an AMD CPU
+    /// with an AMD dGPU are not told apart, and report no discrete GPU.
+    pub fn find_all() -> Result<Vec<Self>, GpuError> {
+        let integrated_vendor = integrated_vendor()?;
+        let entries = fs::read_dir(PCI_DEVICES_PATH).map_err(|error| GpuError::ReadFailed {
+            path: PCI_DEVICES_PATH.into(),
+            error,
+        })?;
+
+        let mut gpus: Vec<Self> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                sysfs::read_trimmed(path.join("class")).is_ok_and(|class| class.starts_with("0x03"))
+            })
+            .filter(|path| {
+                sysfs::read_trimmed(path.join("vendor"))
+                    .is_ok_and(|vendor| Some(vendor.as_str()) != integrated_vendor)
+            })
+            .map(|path| DiscreteGpu { path })
+            .collect();
+        gpus.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(gpus)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the runtime power state, from `power_state` or else `power/runtime_status`.
+    pub fn power_state(&self) -> PowerState {
+        if let Ok(state) = sysfs::read_trimmed(self.path.join("power_state")) {
+            return match state.as_str() {
+                "D0" => PowerState::Active,
+                "D3hot" | "D3cold" => PowerState::Suspended,
+                _ => PowerState::Unknown,
+            };
+        }
+
+        match sysfs::read_trimmed(self.path.join("power/runtime_status")).as_deref() {
+            Ok("active") => PowerState::Active,
+            Ok("suspended") => PowerState::Suspended,
+            _ => PowerState::Unknown,
+        }
+    }
+}
+
+/// PCI vendor id of the integrated GPU, from the CPU vendor.
+fn integrated_vendor() -> Result<Option<&'static str>, GpuError> {
+    let cpuinfo = fs::read_to_string(CPUINFO_PATH).map_err(|error| GpuError::ReadFailed {
+        path: CPUINFO_PATH.into(),
+        error,
+    })?;
+    let cpu_vendor = cpuinfo
+        .lines()
+        .find_map(|line| line.strip_prefix("vendor_id"))
+        .and_then(|rest| rest.split(':').nth(1))
+        .map(str::trim);
+
+    Ok(INTEGRATED_VENDORS
+        .iter()
+        .find(|(cpu, _)| Some(*cpu) == cpu_vendor)
+        .map(|(_, gpu)| *gpu))
+}
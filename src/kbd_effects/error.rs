@@ -0,0 +1,26 @@
+//! Error types and messages for the kbd_effects module.
+
+use crate::common_hardware::kbd_blacklight::KbdBacklightError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KbdEffectsError {
+    #[error("No keyboard or touchpad input device found!")]
+    NoInputDevices,
+
+    #[error("Failed to open input device `{path:?}`, root or the `input` group may be required! {error}")]
+    InputOpenFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("{0}")]
+    KbdBacklightError(#[from] KbdBacklightError),
+}
@@ -0,0 +1,111 @@
+//! Keyboard and touchpad activity from the `/dev/input/event*` devices.
+
+use super::error::KbdEffectsError;
+use crate::sysfs;
+use std::{
+    fs::File,
+    io::{ErrorKind, Read},
+    os::unix::fs::OpenOptionsExt,
+    path::PathBuf,
+};
+
+const INPUT_DEVICES_PATH: &str = "/proc/bus/input/devices";
+
+/// Event devices of the keyboards and pointing devices (touchpads, mice).
+pub(crate) fn find_event_devices() -> Result<Vec<PathBuf>, KbdEffectsError> {
+    let devices =
+        sysfs::read_trimmed(INPUT_DEVICES_PATH).map_err(|error| KbdEffectsError::ReadFailed {
+            path: INPUT_DEVICES_PATH.into(),
+            error,
+        })?;
+
+    Ok(parse_event_devices(&devices))
+}
+
+fn parse_event_devices(devices: &str) -> Vec<PathBuf> {
+    devices
+        .lines()
+        .filter_map(|line| line.strip_prefix("H: Handlers="))
+        .filter(|handlers| {
+            handlers
+                .split_whitespace()
+                .any(|h| h == "kbd" || h.starts_with("mouse"))
+        })
+        .filter_map(|handlers| handlers.split_whitespace().find(|h| h.starts_with("event")))
+        .map(|event| PathBuf::from("/dev/input").join(event))
+        .collect()
+}
+
+/// Non-blocking readers over the event devices, telling whether any input happened.
+pub(crate) struct InputActivity {
+    devices: Vec<File>,
+}
+
+impl InputActivity {
+    pub(crate) fn open(paths: &[PathBuf]) -> Result<Self, KbdEffectsError> {
+        if paths.is_empty() {
+            return Err(KbdEffectsError::NoInputDevices);
+        }
+
+        let devices = paths
+            .iter()
+            .map(|path| {
+                File::options()
+                    .read(true)
+                    .custom_flags(libc::O_NONBLOCK)
+                    .open(path)
+                    .map_err(|error| KbdEffectsError::InputOpenFailed {
+                        path: path.clone(),
+                        error,
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(InputActivity { devices })
+    }
+
+    /// Drain the pending events, returning `true` if there were any.
+    pub(crate) fn poll(&mut self) -> bool {
+        let mut buf = [0_u8; 1024];
+        let mut active = false;
+
+        for device in &mut self.devices {
+            loop {
+                match device.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(_) => active = true,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    // WouldBlock once drained, other errors (device unplugged) are ignored
+                    Err(_) => break,
+                }
+            }
+        }
+
+        active
+    }
+}
+
+#[test]
+fn event_devices_from_proc() {
+    let devices = "\
+I: Bus=0011 Vendor=0001 Product=0001 Version=ab83
+N: Name=\"AT Translated Set 2 keyboard\"
+H: Handlers=sysrq kbd leds event3
+
+I: Bus=0018 Vendor=04f3 Product=3140 Version=0100
+N: Name=\"ASUE1409:00 04F3:3140 Touchpad\"
+H: Handlers=mouse0 event8
+
+I: Bus=0019 Vendor=0000 Product=0006 Version=0000
+N: Name=\"Video Bus\"
+H: Handlers=event5
+";
+
+    assert_eq!(
+        parse_event_devices(devices),
+        [
+            PathBuf::from("/dev/input/event3"),
+            PathBuf::from("/dev/input/event8")
+        ]
+    );
+}
@@ -0,0 +1,150 @@
+//! Keyboard backlight effects: smooth fades and idle auto-off.
+//!
+//! Built on [KbdBacklight], every effect runs on a background [Worker] which can be
+//! cancelled by stopping or dropping it.
+
+pub mod error;
+mod input;
+
+use crate::{
+    common_hardware::kbd_blacklight::{KbdBacklight, KbdBacklightError},
+    worker::{StopSignal, Worker},
+};
+use error::KbdEffectsError;
+use input::InputActivity;
+use std::time::{Duration, Instant};
+
+/// Step through the levels from `from` to `to`, spread evenly over `duration`.
+///
+/// Returns `false` if interrupted by the signal.
+fn step_levels(
+    backlight: &KbdBacklight,
+    from: u8,
+    to: u8,
+    duration: Duration,
+    signal: &StopSignal,
+) -> Result<bool, KbdBacklightError> {
+    let steps = from.abs_diff(to);
+    if steps == 0 {
+        return Ok(true);
+    }
+
+    let interval = duration / u32::from(steps);
+    let levels: Vec<u8> = if from < to {
+        (from + 1..=to).collect()
+    } else {
+        (to..from).rev().collect()
+    };
+
+    for level in levels {
+        if signal.sleep(interval) {
+            return Ok(false);
+        }
+        backlight.set(level)?;
+    }
+
+    Ok(true)
+}
+
+/// Fade the backlight from its current level to `to` over `duration`, on a background thread.
+///
+/// Stopping the worker leaves the backlight at the level reached so far.
+pub fn fade(
+    backlight: &KbdBacklight,
+    to: u8,
+    duration: Duration,
+) -> Worker<Result<(), KbdBacklightError>> {
+    let backlight = backlight.clone();
+    Worker::spawn(move |signal| {
+        let from = backlight.read()?;
+        step_levels(&backlight, from, to, duration, &signal)?;
+        Ok(())
+    })
+}
+
+/// Turns the backlight off after a period without keyboard or touchpad input,
+/// and restores it on the next input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleAutoOff {
+    /// Time without input before turning the backlight off.
+    pub timeout: Duration,
+    /// Duration of the fade when turning off and restoring.
+    pub fade: Duration,
+    /// How often the input devices are checked.
+    pub poll_interval: Duration,
+}
+
+impl Default for IdleAutoOff {
+    fn default() -> Self {
+        IdleAutoOff {
+            timeout: Duration::from_secs(30),
+            fade: Duration::from_millis(600),
+            poll_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+impl IdleAutoOff {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        IdleAutoOff {
+            timeout,
+            ..Default::default()
+        }
+    }
+
+    /// Start watching the input devices on a background thread.
+    ///
+    /// Opening `/dev/input/event*` usually requires root or the `input` group.
+    /// When the worker is stopped, a backlight turned off by it is restored.
+    pub fn start(
+        self,
+        backlight: &KbdBacklight,
+    ) -> Result<Worker<Result<(), KbdEffectsError>>, KbdEffectsError> {
+        let mut activity = InputActivity::open(&input::find_event_devices()?)?;
+        let backlight = backlight.clone();
+
+        Ok(Worker::spawn(move |signal| {
+            let mut last_input = Instant::now();
+            // the backlight is only read once per idle period, when the timeout passes
+            let mut idle = false;
+            // level to restore while the backlight is turned off by us
+            let mut dimmed_from: Option<u8> = None;
+
+            let result = loop {
+                if signal.sleep(self.poll_interval) {
+                    break Ok(());
+                }
+
+                if activity.poll() {
+                    last_input = Instant::now();
+                    idle = false;
+                    if let Some(level) = dimmed_from {
+                        match step_levels(&backlight, 0, level, self.fade, &signal) {
+                            Ok(true) => dimmed_from = None,
+                            // stopped midway, restored below
+                            Ok(false) => {}
+                            Err(e) => break Err(e.into()),
+                        }
+                    }
+                } else if !idle && last_input.elapsed() >= self.timeout {
+                    idle = true;
+                    let level = match backlight.read() {
+                        Ok(level) => level,
+                        Err(e) => break Err(e.into()),
+                    };
+                    if level > 0 {
+                        dimmed_from = Some(level);
+                        if let Err(e) = step_levels(&backlight, level, 0, self.fade, &signal) {
+                            break Err(e.into());
+                        }
+                    }
+                }
+            };
+
+            if let Some(level) = dimmed_from {
+                backlight.set(level)?;
+            }
+            result
+        }))
+    }
+}
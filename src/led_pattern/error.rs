@@ -0,0 +1,24 @@
+//! Error types and messages for the led_pattern module.
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PatternError {
+    #[error("Unknown pattern item `{token}`! Expected one of `on:MS`, `off:MS`, `blink:N[@MS]`, `pulse:N`, `morse:TEXT[@MS]` or `repeat[:N]`.")]
+    UnknownItem { token: String },
+
+    #[error("Invalid number in pattern item `{token}`!")]
+    InvalidNumber { token: String },
+
+    #[error("Character `{character}` has no Morse code!")]
+    UnsupportedCharacter { character: char },
+
+    #[error("`repeat` must be the last item of the pattern!")]
+    RepeatNotLast,
+
+    #[error("Repeated patterns need at least one step lasting more than zero!")]
+    ZeroDurationRepeat,
+
+    #[error("The pattern is empty!")]
+    Empty,
+}
@@ -0,0 +1,362 @@
+//! Blink patterns for the on/off LEDs such as [camera_led](crate::common_hardware::camera_led)
+//! and [mic_led](crate::common_hardware::mic_led), to use them as signalling lights.
+//!
+//! A [LedPattern] is a sequence of on/off steps, built from durations, from the helpers
+//! ([blink](LedPattern::blink), [pulse](LedPattern::pulse), [morse](LedPattern::morse)) or
+//! parsed from a small syntax of whitespace separated items:
+//!
+//! - `on:MS`, `off:MS` turn the LED on or off for the given milliseconds
+//! - `blink:N[@MS]` blinks N times, 250ms by default
+//! - `pulse:N` double blinks N times, like a heartbeat
+//! - `morse:TEXT[@MS]` spells the text in Morse code, with 150ms dots by default
+//! - `repeat` (forever) or `repeat:N`, as the last item
+//!
+//! Example: `morse:SOS off:1000 repeat:3`.
+//!
+//! The pattern is played on a background [Worker], and the LED is restored to its original
+//! state when the pattern ends or the worker is stopped.
+
+pub mod error;
+
+use crate::{
+    common_hardware::led_state::LedState,
+    debugfs::{error::HardwareError, Hardware},
+    worker::Worker,
+};
+use error::PatternError;
+use std::{str::FromStr, time::Duration};
+
+const BLINK_MS: u64 = 250;
+const MORSE_UNIT_MS: u64 = 150;
+
+/// LED state held for a duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub state: LedState,
+    pub duration: Duration,
+}
+
+impl Step {
+    pub const fn on(duration: Duration) -> Self {
+        Step {
+            state: LedState::On,
+            duration,
+        }
+    }
+
+    pub const fn off(duration: Duration) -> Self {
+        Step {
+            state: LedState::Off,
+            duration,
+        }
+    }
+}
+
+/// How many times the pattern is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+    Times(u32),
+    Forever,
+}
+
+/// A sequence of on/off steps to play on an LED.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedPattern {
+    steps: Vec<Step>,
+    repeat: Repeat,
+}
+
+impl LedPattern {
+    /// Pattern playing the steps once.
+    pub fn new(steps: Vec<Step>) -> Self {
+        LedPattern {
+            steps,
+            repeat: Repeat::Times(1),
+        }
+    }
+
+    /// Pattern alternating on and off for the given durations, starting with on.
+    pub fn from_durations(durations: &[Duration]) -> Self {
+        Self::new(
+            durations
+                .iter()
+                .enumerate()
+                .map(|(i, duration)| {
+                    if i % 2 == 0 {
+                        Step::on(*duration)
+                    } else {
+                        Step::off(*duration)
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Blink `times` times, on and off for `period` each.
+    pub fn blink(times: u32, period: Duration) -> Self {
+        Self::new(
+            (0..times)
+                .flat_map(|_| [Step::on(period), Step::off(period)])
+                .collect(),
+        )
+    }
+
+    /// Double blink `times` times, like a heartbeat.
+    pub fn pulse(times: u32) -> Self {
+        let short = Duration::from_millis(120);
+        Self::new(
+            (0..times)
+                .flat_map(|_| {
+                    [
+                        Step::on(short),
+                        Step::off(short),
+                        Step::on(short),
+                        Step::off(Duration::from_millis(700)),
+                    ]
+                })
+                .collect(),
+        )
+    }
+
+    /// Spell the text in Morse code, with dots lasting `unit`.
+    pub fn morse(text: &str, unit: Duration) -> Result<Self, PatternError> {
+        let mut steps = Vec::new();
+
+        for (i, word) in text.split_whitespace().enumerate() {
+            if i > 0 {
+                steps.push(Step::off(unit * 7));
+            }
+            for (j, character) in word.chars().enumerate() {
+                if j > 0 {
+                    steps.push(Step::off(unit * 3));
+                }
+                let code = morse_code(character)
+                    .ok_or(PatternError::UnsupportedCharacter { character })?;
+                for (k, symbol) in code.chars().enumerate() {
+                    if k > 0 {
+                        steps.push(Step::off(unit));
+                    }
+                    steps.push(Step::on(if symbol == '-' { unit * 3 } else { unit }));
+                }
+            }
+        }
+
+        // keep a gap before the pattern repeats
+        if !steps.is_empty() {
+            steps.push(Step::off(unit * 7));
+        }
+
+        Ok(Self::new(steps))
+    }
+
+    /// Set how many times the pattern is played.
+    ///
+    /// Fails if every step lasts zero, as repeating them would write to the LED in a
+    /// tight loop.
+    pub fn repeat(mut self, repeat: Repeat) -> Result<Self, PatternError> {
+        if repeat != Repeat::Times(1)
+            && !self.steps.is_empty()
+            && self.steps.iter().all(|step| step.duration.is_zero())
+        {
+            return Err(PatternError::ZeroDurationRepeat);
+        }
+        self.repeat = repeat;
+        Ok(self)
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    pub const fn repetitions(&self) -> Repeat {
+        self.repeat
+    }
+
+    /// Play the pattern on the LED on a background thread.
+    ///
+    /// The original state is read first, and restored when the pattern ends or the
+    /// worker is stopped.
+    pub fn play(
+        &self,
+        led: Hardware<LedState>,
+    ) -> Result<Worker<Result<(), HardwareError>>, HardwareError> {
+        let original = led.read()?;
+        let pattern = self.clone();
+
+        Ok(Worker::spawn(move |signal| {
+            let mut played = 0;
+            let result = 'play: loop {
+                match pattern.repeat {
+                    Repeat::Times(times) if played >= times => break Ok(()),
+                    _ => played += 1,
+                }
+                if pattern.steps.is_empty() {
+                    break Ok(());
+                }
+
+                for step in &pattern.steps {
+                    if let Err(e) = led.apply(step.state) {
+                        break 'play Err(e);
+                    }
+                    if signal.sleep(step.duration) {
+                        break 'play Ok(());
+                    }
+                }
+            };
+
+            let restored = led.apply(original);
+            result.and(restored)
+        }))
+    }
+}
+
+impl FromStr for LedPattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        let mut repeat = Repeat::Times(1);
+        let mut tokens = s.split_whitespace().peekable();
+
+        while let Some(token) = tokens.next() {
+            let (item, arg) = token.split_once(':').unwrap_or((token, ""));
+            let (arg, at) = arg
+                .split_once('@')
+                .map_or((arg, None), |(a, ms)| (a, Some(ms)));
+
+            let invalid = || PatternError::InvalidNumber {
+                token: token.to_string(),
+            };
+            let number = |value: &str| value.parse::<u64>().map_err(|_| invalid());
+            let count = |value: &str| value.parse::<u32>().map_err(|_| invalid());
+            let millis = |value: Option<&str>, default| {
+                value.map_or(Ok(default), number).map(Duration::from_millis)
+            };
+
+            match item {
+                "on" => steps.push(Step::on(Duration::from_millis(number(arg)?))),
+                "off" => steps.push(Step::off(Duration::from_millis(number(arg)?))),
+                "blink" => steps.extend(Self::blink(count(arg)?, millis(at, BLINK_MS)?).steps),
+                "pulse" => steps.extend(Self::pulse(count(arg)?).steps),
+                "morse" => steps.extend(Self::morse(arg, millis(at, MORSE_UNIT_MS)?)?.steps),
+                "repeat" => {
+                    if tokens.peek().is_some() {
+                        return Err(PatternError::RepeatNotLast);
+                    }
+                    repeat = if arg.is_empty() {
+                        Repeat::Forever
+                    } else {
+                        Repeat::Times(count(arg)?)
+                    };
+                }
+                _ => {
+                    return Err(PatternError::UnknownItem {
+                        token: token.to_string(),
+                    })
+                }
+            }
+        }
+
+        if steps.is_empty() {
+            return Err(PatternError::Empty);
+        }
+        Self::new(steps).repeat(repeat)
+    }
+}
+
+fn morse_code(character: char) -> Option<&'static str> {
+    Some(match character.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        _ => return None,
+    })
+}
+
+#[test]
+fn parse_pattern() {
+    let ms = Duration::from_millis;
+
+    let pattern: LedPattern = "on:100 off:50 blink:1@20 repeat:3".parse().unwrap();
+    assert_eq!(
+        pattern.steps(),
+        [
+            Step::on(ms(100)),
+            Step::off(ms(50)),
+            Step::on(ms(20)),
+            Step::off(ms(20))
+        ]
+    );
+    assert_eq!(pattern.repetitions(), Repeat::Times(3));
+
+    let sos = "morse:SOS@10".parse::<LedPattern>().unwrap();
+    assert_eq!(sos, LedPattern::morse("sos", ms(10)).unwrap());
+    // 3 dots, 3 dashes, 3 dots, and the gaps between them
+    assert_eq!(
+        sos.steps()
+            .iter()
+            .filter(|s| s.state == LedState::On)
+            .count(),
+        9
+    );
+
+    assert_eq!(
+        "repeat blink:2".parse::<LedPattern>(),
+        Err(PatternError::RepeatNotLast)
+    );
+    assert!(matches!(
+        "glow:2".parse::<LedPattern>(),
+        Err(PatternError::UnknownItem { .. })
+    ));
+    assert!(matches!(
+        "blink:4294967297".parse::<LedPattern>(),
+        Err(PatternError::InvalidNumber { .. })
+    ));
+    assert_eq!(
+        "on:0 off:0 repeat".parse::<LedPattern>(),
+        Err(PatternError::ZeroDurationRepeat)
+    );
+    assert!("on:0 off:0".parse::<LedPattern>().is_ok());
+    assert!("on:0 off:100 repeat".parse::<LedPattern>().is_ok());
+    assert_eq!(
+        LedPattern::new(vec![Step::on(Duration::ZERO)]).repeat(Repeat::Forever),
+        Err(PatternError::ZeroDurationRepeat)
+    );
+    assert_eq!(
+        "morse:a!".parse::<LedPattern>(),
+        Err(PatternError::UnsupportedCharacter { character: '!' })
+    );
+}
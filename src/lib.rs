@@ -15,9 +15,57 @@
 
 #![cfg(target_os = "linux")]
 
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod als;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod battery;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod camera;
 pub mod debugfs;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod form_factor;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod gpu;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod kbd_effects;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod led_pattern;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod mic_mute;
+pub mod persist;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod platform_profile;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod power_limits;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod radios;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod rgb;
+#[cfg(feature = "common-hardware")]
+#[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+pub mod screenpad;
+pub mod sensors;
 #[cfg(feature = "pwm")]
 #[cfg_attr(docsrs, doc(cfg(feature = "pwm")))]
 pub mod pwm;
-pub use debugfs::{common_hardware, error};
+mod sysfs;
+#[cfg(any(feature = "common-hardware", feature = "pwm"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "common-hardware", feature = "pwm"))))]
+pub mod worker;
+
+#[cfg(feature = "common-hardware")]
+pub use debugfs::common_hardware;
+pub use debugfs::error;
 pub use debugfs::{Config, Hardware};
@@ -0,0 +1,163 @@
+//! Capture mute state read from the ALSA mixer controls.
+
+use super::{error::MicMuteError, MuteSource};
+use crate::sysfs;
+use std::{
+    fs::File,
+    os::fd::AsRawFd,
+    path::{Path, PathBuf},
+};
+
+const CARDS_PATH: &str = "/proc/asound/cards";
+
+/// `SNDRV_CTL_ELEM_IFACE_MIXER`
+const IFACE_MIXER: i32 = 2;
+
+/// `struct snd_ctl_elem_id` from `<sound/asound.h>`.
+#[repr(C)]
+struct ElemId {
+    numid: u32,
+    iface: i32,
+    device: u32,
+    subdevice: u32,
+    name: [u8; 44],
+    index: u32,
+}
+
+/// `struct snd_ctl_elem_value` from `<sound/asound.h>`, with the value read as integers.
+#[repr(C)]
+struct ElemValue {
+    id: ElemId,
+    indirect: u32,
+    value: [libc::c_long; 128],
+    reserved: [u8; 128],
+}
+
+/// `SNDRV_CTL_IOCTL_ELEM_READ`, `_IOWR('U', 0x12, struct snd_ctl_elem_value)`.
+const IOCTL_ELEM_READ: u64 =
+    (3 << 30) | ((std::mem::size_of::<ElemValue>() as u64) << 16) | ((b'U' as u64) << 8) | 0x12;
+
+/// Card numbers listed in `/proc/asound/cards`.
+fn cards() -> Result<Vec<u32>, MicMuteError> {
+    let cards = sysfs::read_trimmed(CARDS_PATH).map_err(|error| MicMuteError::ReadFailed {
+        path: CARDS_PATH.into(),
+        error,
+    })?;
+
+    Ok(parse_cards(&cards))
+}
+
+fn parse_cards(cards: &str) -> Vec<u32> {
+    cards
+        .lines()
+        // card lines start with the number, their description lines are indented further
+        .filter_map(|line| line.split_once('[').map(|(number, _)| number.trim()))
+        .filter_map(|number| number.parse().ok())
+        .collect()
+}
+
+/// Read a boolean mixer control, returning `true` if any channel is switched on.
+fn read_switch(control: &File, name: &str) -> std::io::Result<bool> {
+    let mut value = ElemValue {
+        id: ElemId {
+            numid: 0,
+            iface: IFACE_MIXER,
+            device: 0,
+            subdevice: 0,
+            name: [0; 44],
+            index: 0,
+        },
+        indirect: 0,
+        value: [0; 128],
+        reserved: [0; 128],
+    };
+    let len = name.len().min(value.id.name.len() - 1);
+    value.id.name[..len].copy_from_slice(&name.as_bytes()[..len]);
+
+    // SAFETY: the request matches the layout of `value`, which outlives the call
+    let result = unsafe {
+        libc::ioctl(
+            control.as_raw_fd(),
+            IOCTL_ELEM_READ as _,
+            &mut value as *mut ElemValue,
+        )
+    };
+    if result < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(value.value.iter().any(|channel| *channel != 0))
+}
+
+/// Reads the capture switch of an ALSA card through the control ioctls.
+///
+/// The capture is muted when every channel of the switch is off.
+#[derive(Debug)]
+pub struct AlsaCaptureSource {
+    path: PathBuf,
+    control: File,
+    name: String,
+}
+
+impl AlsaCaptureSource {
+    /// Name of the capture switch control on most codecs.
+    pub const CAPTURE_SWITCH: &'static str = "Capture Switch";
+
+    /// Use the named switch on the given card.
+    pub fn new(card: u32, name: &str) -> Result<Self, MicMuteError> {
+        let path = PathBuf::from(format!("/dev/snd/controlC{card}"));
+        let control = File::open(&path).map_err(|error| MicMuteError::ReadFailed {
+            path: path.clone(),
+            error,
+        })?;
+
+        Ok(AlsaCaptureSource {
+            path,
+            control,
+            name: name.to_string(),
+        })
+    }
+
+    /// Use the first card, listed in `/proc/asound/cards`, with a `Capture Switch`.
+    pub fn detect() -> Result<Self, MicMuteError> {
+        cards()?
+            .into_iter()
+            .filter_map(|card| Self::new(card, Self::CAPTURE_SWITCH).ok())
+            .find(|source| read_switch(&source.control, &source.name).is_ok())
+            .ok_or(MicMuteError::ControlNotFound {
+                control: Self::CAPTURE_SWITCH.to_string(),
+            })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl MuteSource for AlsaCaptureSource {
+    fn is_muted(&mut self) -> Result<bool, MicMuteError> {
+        read_switch(&self.control, &self.name)
+            .map(|on| !on)
+            .map_err(|error| MicMuteError::ReadFailed {
+                path: self.path.clone(),
+                error,
+            })
+    }
+}
+
+#[test]
+fn elem_value_layout() {
+    assert_eq!(std::mem::size_of::<ElemId>(), 64);
+    #[cfg(target_pointer_width = "64")]
+    assert_eq!(IOCTL_ELEM_READ, 0xc4c8_5512);
+}
+
+#[test]
+fn cards_from_proc() {
+    let cards = " 0 [PCH            ]: HDA-Intel - HDA Intel PCH
+                      HDA Intel PCH at 0x6001180000 irq 171
+ 1 [NVidia         ]: HDA-Intel - HDA NVidia
+                      HDA NVidia at 0x84080000 irq 17";
+
+    assert_eq!(parse_cards(cards), [0, 1]);
+}
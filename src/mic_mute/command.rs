@@ -0,0 +1,85 @@
+//! Capture mute state read from the output of a command, for PipeWire/PulseAudio setups.
+
+use super::{error::MicMuteError, MuteSource};
+use std::process::Command;
+
+/// Runs a command and parses its output to tell whether the capture is muted.
+#[derive(Debug, Clone)]
+pub struct CommandSource {
+    program: String,
+    args: Vec<String>,
+    parse: fn(&str) -> Option<bool>,
+}
+
+impl CommandSource {
+    /// Run `program` with `args`, `parse` returning whether the output means muted.
+    pub fn new(program: &str, args: &[&str], parse: fn(&str) -> Option<bool>) -> Self {
+        CommandSource {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            parse,
+        }
+    }
+
+    /// `pactl get-source-mute @DEFAULT_SOURCE@`, printing `Mute: yes` or `Mute: no`.
+    pub fn pactl() -> Self {
+        Self::new(
+            "pactl",
+            &["get-source-mute", "@DEFAULT_SOURCE@"],
+            parse_pactl,
+        )
+    }
+
+    /// `wpctl get-volume @DEFAULT_AUDIO_SOURCE@`, printing `Volume: 0.40 [MUTED]` when muted.
+    pub fn wpctl() -> Self {
+        Self::new(
+            "wpctl",
+            &["get-volume", "@DEFAULT_AUDIO_SOURCE@"],
+            parse_wpctl,
+        )
+    }
+}
+
+fn parse_pactl(output: &str) -> Option<bool> {
+    match output.trim().strip_prefix("Mute:")?.trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_wpctl(output: &str) -> Option<bool> {
+    let output = output.trim();
+    output
+        .starts_with("Volume:")
+        .then(|| output.contains("[MUTED]"))
+}
+
+impl MuteSource for CommandSource {
+    fn is_muted(&mut self) -> Result<bool, MicMuteError> {
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .output()
+            .map_err(|error| MicMuteError::CommandFailed {
+                program: self.program.clone(),
+                error,
+            })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        (self.parse)(&stdout).ok_or_else(|| MicMuteError::UnexpectedOutput {
+            program: self.program.clone(),
+            output: stdout.into_owned(),
+        })
+    }
+}
+
+#[test]
+fn parse_command_output() {
+    assert_eq!(parse_pactl("Mute: yes\n"), Some(true));
+    assert_eq!(parse_pactl("Mute: no\n"), Some(false));
+    assert_eq!(parse_pactl("Connection failure"), None);
+
+    assert_eq!(parse_wpctl("Volume: 0.40 [MUTED]\n"), Some(true));
+    assert_eq!(parse_wpctl("Volume: 0.40\n"), Some(false));
+    assert_eq!(parse_wpctl(""), None);
+}
@@ -0,0 +1,29 @@
+//! Error types and messages for the mic_mute module.
+
+use crate::debugfs::error::HardwareError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MicMuteError {
+    #[error("No sound card with a `{control}` control found!")]
+    ControlNotFound { control: String },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to run `{program}`! {error}")]
+    CommandFailed {
+        program: String,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected output of `{program}`: `{output}`")]
+    UnexpectedOutput { program: String, output: String },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
@@ -0,0 +1,88 @@
+//! Keeps [mic LED](crate::common_hardware::mic_led) in step with the capture mute state
+//! of the audio stack.
+//!
+//! The mute state comes from a [MuteSource]: [AlsaCaptureSource] reads the capture switch
+//! of the sound card directly, while [CommandSource] asks PipeWire/PulseAudio through
+//! `wpctl` or `pactl`. [MicMuteSync] checks the source on a background thread, so the led
+//! follows a mute toggle within the configured interval.
+
+mod alsa;
+mod command;
+pub mod error;
+
+pub use alsa::AlsaCaptureSource;
+pub use command::CommandSource;
+
+use crate::{
+    common_hardware::{led_state::LedState, mic_led},
+    worker::Worker,
+};
+use error::MicMuteError;
+use std::time::Duration;
+
+/// Source of the capture mute state.
+pub trait MuteSource: Send {
+    /// Whether the capture is currently muted.
+    fn is_muted(&mut self) -> Result<bool, MicMuteError>;
+}
+
+/// Drives the mic-mute led from a [MuteSource]: lit while the capture is muted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MicMuteSync {
+    /// How often the source is checked, bounding the latency of the led.
+    pub interval: Duration,
+    /// Consecutive failures of the source tolerated before giving up.
+    pub max_failures: u32,
+}
+
+impl Default for MicMuteSync {
+    fn default() -> Self {
+        MicMuteSync {
+            interval: Duration::from_millis(200),
+            max_failures: 10,
+        }
+    }
+}
+
+impl MicMuteSync {
+    pub fn with_interval(interval: Duration) -> Self {
+        MicMuteSync {
+            interval,
+            ..Default::default()
+        }
+    }
+
+    /// Start following the source on a background thread.
+    ///
+    /// The led is applied right away, then on every change of the mute state. The worker
+    /// ends with the last error after `max_failures` consecutive failures of the source.
+    pub fn start(self, mut source: impl MuteSource + 'static) -> Worker<Result<(), MicMuteError>> {
+        Worker::spawn(move |signal| {
+            let led = mic_led::get();
+            let mut last = None;
+            let mut failures = 0;
+
+            loop {
+                match source.is_muted() {
+                    Ok(muted) => {
+                        failures = 0;
+                        if last != Some(muted) {
+                            led.apply(if muted { LedState::On } else { LedState::Off })?;
+                            last = Some(muted);
+                        }
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        if failures >= self.max_failures {
+                            return Err(e);
+                        }
+                    }
+                }
+
+                if signal.sleep(self.interval) {
+                    return Ok(());
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,183 @@
+//! Persistence of the desired hardware states, to re-apply them after the firmware
+//! resets them (at boot or after suspend).
+//!
+//! States are stored under `/var/lib/meh-asus`, one file per hardware, so a boot-time
+//! or resume-time hook can call [restore](Persistent::restore) to bring back the user's choice.
+
+use crate::{
+    debugfs::{error::HardwareError, Config, Hardware},
+    sysfs,
+};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Directory where the crate keeps its state across reboots.
+pub const STATE_DIR: &str = "/var/lib/meh-asus";
+
+#[derive(Debug, Error)]
+pub enum PersistError {
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to write `{path:?}`! {error}")]
+    WriteFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("The value `{value}` stored in `{path:?}` is not a valid state!")]
+    InvalidValue { path: PathBuf, value: String },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
+
+/// Hardware whose desired state is stored on disk.
+#[derive(Debug, Clone)]
+pub struct Persistent<State>
+where
+    State: Config,
+{
+    hardware: Hardware<State>,
+    path: PathBuf,
+    mask: Option<u64>,
+}
+
+impl<State> Persistent<State>
+where
+    State: Config,
+{
+    /// Store the state of the hardware under [STATE_DIR] with the given name.
+    pub fn new(name: &str, hardware: Hardware<State>) -> Self {
+        Self::with_path(Path::new(STATE_DIR).join(name), hardware)
+    }
+
+    /// Store the state of the hardware in the given file.
+    pub fn with_path(path: impl Into<PathBuf>, hardware: Hardware<State>) -> Self {
+        Persistent {
+            hardware,
+            path: path.into(),
+            mask: None,
+        }
+    }
+
+    /// Read the state with [read_masked](Hardware::read_masked) when taking a
+    /// [snapshot](Persistent::snapshot), so the hardware is left untouched.
+    pub fn masked(mut self, mask: u64) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub fn hardware(&self) -> &Hardware<State> {
+        &self.hardware
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Store the state as the desired one, without applying it.
+    pub fn save(&self, state: State) -> Result<(), PersistError> {
+        let write = |path: &Path| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            sysfs::write(path, state.to_config())
+        };
+
+        write(&self.path).map_err(|error| PersistError::WriteFailed {
+            path: self.path.clone(),
+            error,
+        })
+    }
+
+    /// Apply the state to the hardware and store it as the desired one.
+    pub fn apply(&self, state: State) -> Result<(), PersistError> {
+        self.hardware.apply(state)?;
+        self.save(state)
+    }
+
+    /// Store the current state of the hardware as the desired one, returning it.
+    ///
+    /// Without a [mask](Persistent::masked), the state is found with [read](Hardware::read)
+    /// which briefly applies `0` to the hardware.
+    pub fn snapshot(&self) -> Result<State, PersistError> {
+        let state = match self.mask {
+            Some(mask) => self.hardware.read_masked(mask)?,
+            None => self.hardware.read()?,
+        };
+        self.save(state)?;
+        Ok(state)
+    }
+
+    /// Read the stored state, `None` if nothing was stored.
+    pub fn load(&self) -> Result<Option<State>, PersistError> {
+        let value = match sysfs::read_trimmed(&self.path) {
+            Ok(value) => value,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(PersistError::ReadFailed {
+                    path: self.path.clone(),
+                    error,
+                })
+            }
+        };
+
+        value
+            .parse::<u64>()
+            .ok()
+            .and_then(|raw| State::try_from(raw).ok())
+            .map(Some)
+            .ok_or(PersistError::InvalidValue {
+                path: self.path.clone(),
+                value,
+            })
+    }
+
+    /// Re-apply the stored state, returning it, or `None` if nothing was stored.
+    pub fn restore(&self) -> Result<Option<State>, PersistError> {
+        let state = self.load()?;
+        if let Some(state) = state {
+            self.hardware.apply(state)?;
+        }
+        Ok(state)
+    }
+
+    /// Remove the stored state.
+    pub fn forget(&self) -> Result<(), PersistError> {
+        match fs::remove_file(&self.path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(PersistError::WriteFailed {
+                path: self.path.clone(),
+                error,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn save_and_load() {
+    let path = std::env::temp_dir().join(format!("meh-asus-persist-{}", std::process::id()));
+    let persistent = Persistent::with_path(&path, Hardware::<u8>::new(0));
+
+    assert_eq!(persistent.load().unwrap(), None);
+
+    persistent.save(3).unwrap();
+    assert_eq!(persistent.load().unwrap(), Some(3));
+
+    sysfs::write(&path, "300").unwrap();
+    assert!(matches!(
+        persistent.load(),
+        Err(PersistError::InvalidValue { .. })
+    ));
+
+    persistent.forget().unwrap();
+    assert_eq!(persistent.load().unwrap(), None);
+}
@@ -0,0 +1,35 @@
+//! Error types and messages for the platform_profile module.
+
+use super::PlatformProfile;
+use crate::common_hardware::thermal_policy::{ThermalPolicyError, ThermalProfile};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PlatformProfileError {
+    #[error("ACPI platform_profile is not available on this machine! {error}")]
+    Unsupported { error: std::io::Error },
+
+    #[error("Failed to read the platform profile! {error}")]
+    ReadFailed { error: std::io::Error },
+
+    #[error("Failed to write the platform profile `{profile}`! {error}")]
+    WriteFailed {
+        profile: PlatformProfile,
+        error: std::io::Error,
+    },
+
+    #[error("Unknown platform profile `{value}`!")]
+    UnknownProfile { value: String },
+
+    #[error("Platform profile `{profile}` is not one of the available choices!")]
+    NotAChoice { profile: PlatformProfile },
+
+    #[error("No platform profile choice corresponds to thermal profile `{profile:?}`!")]
+    NoMatchingChoice { profile: ThermalProfile },
+
+    #[error("Platform profile `{profile}` has no matching thermal profile!")]
+    NoMatchingThermalProfile { profile: PlatformProfile },
+
+    #[error("{0}")]
+    ThermalPolicyError(#[from] ThermalPolicyError),
+}
@@ -0,0 +1,292 @@
+//! Integration with the kernel's ACPI `platform_profile`.
+//!
+//! `/sys/firmware/acpi/platform_profile` is what GNOME, KDE and power-profiles-daemon
+//! use to show and switch the power mode. On ASUS laptops the asus-wmi driver backs it
+//! with the same thermal policy as [thermal_policy](crate::common_hardware::thermal_policy)
+//! and [fan](crate::common_hardware::fan), so this module maps between them.
+//!
+//! Prefer applying profiles through [ProfileController] with the platform_profile backend:
+//! writing the DEV_ID directly is not noticed by the driver, and the desktop indicator would
+//! keep showing the stale mode.
+
+pub mod error;
+
+use crate::common_hardware::{
+    fan::FanMode,
+    thermal_policy::{ThermalPolicy, ThermalProfile},
+};
+use crate::sysfs;
+use error::PlatformProfileError;
+use std::{fmt, io::ErrorKind, str::FromStr};
+
+const PROFILE_PATH: &str = "/sys/firmware/acpi/platform_profile";
+const CHOICES_PATH: &str = "/sys/firmware/acpi/platform_profile_choices";
+
+/// Profiles known to the ACPI platform_profile interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlatformProfile {
+    LowPower,
+    Cool,
+    Quiet,
+    Balanced,
+    BalancedPerformance,
+    Performance,
+    Custom,
+}
+
+impl PlatformProfile {
+    /// Name of the profile as used by the kernel.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            PlatformProfile::LowPower => "low-power",
+            PlatformProfile::Cool => "cool",
+            PlatformProfile::Quiet => "quiet",
+            PlatformProfile::Balanced => "balanced",
+            PlatformProfile::BalancedPerformance => "balanced-performance",
+            PlatformProfile::Performance => "performance",
+            PlatformProfile::Custom => "custom",
+        }
+    }
+
+    /// Thermal profile corresponding to the platform profile, if any.
+    pub const fn thermal_profile(self) -> Option<ThermalProfile> {
+        match self {
+            PlatformProfile::LowPower | PlatformProfile::Cool | PlatformProfile::Quiet => {
+                Some(ThermalProfile::Quiet)
+            }
+            PlatformProfile::Balanced => Some(ThermalProfile::Balanced),
+            PlatformProfile::BalancedPerformance | PlatformProfile::Performance => {
+                Some(ThermalProfile::Performance)
+            }
+            PlatformProfile::Custom => None,
+        }
+    }
+
+    /// Pick the platform profile among `choices` that corresponds to the thermal profile.
+    ///
+    /// [FullSpeed](ThermalProfile::FullSpeed) has no platform profile of its own and is
+    /// mapped to [Performance](PlatformProfile::Performance).
+    pub fn from_thermal_profile(
+        profile: ThermalProfile,
+        choices: &[PlatformProfile],
+    ) -> Option<PlatformProfile> {
+        let candidates: &[PlatformProfile] = match profile {
+            ThermalProfile::Quiet => &[
+                PlatformProfile::Quiet,
+                PlatformProfile::LowPower,
+                PlatformProfile::Cool,
+            ],
+            ThermalProfile::Balanced => &[PlatformProfile::Balanced],
+            ThermalProfile::Performance | ThermalProfile::FullSpeed => &[
+                PlatformProfile::Performance,
+                PlatformProfile::BalancedPerformance,
+            ],
+        };
+
+        candidates
+            .iter()
+            .copied()
+            .find(|candidate| choices.contains(candidate))
+    }
+
+    /// Fan mode corresponding to the platform profile, if any.
+    pub fn fan_mode(self) -> Option<FanMode> {
+        self.thermal_profile().map(FanMode::from)
+    }
+}
+
+impl fmt::Display for PlatformProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PlatformProfile {
+    type Err = PlatformProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "low-power" => PlatformProfile::LowPower,
+            "cool" => PlatformProfile::Cool,
+            "quiet" => PlatformProfile::Quiet,
+            "balanced" => PlatformProfile::Balanced,
+            "balanced-performance" => PlatformProfile::BalancedPerformance,
+            "performance" => PlatformProfile::Performance,
+            "custom" => PlatformProfile::Custom,
+            value => {
+                return Err(PlatformProfileError::UnknownProfile {
+                    value: value.to_string(),
+                })
+            }
+        })
+    }
+}
+
+fn read_attr(path: &str) -> Result<String, PlatformProfileError> {
+    sysfs::read_trimmed(path).map_err(|error| match error.kind() {
+        ErrorKind::NotFound => PlatformProfileError::Unsupported { error },
+        _ => PlatformProfileError::ReadFailed { error },
+    })
+}
+
+/// Check whether the ACPI platform_profile interface is available.
+pub fn is_available() -> bool {
+    std::path::Path::new(PROFILE_PATH).exists()
+}
+
+/// Read the profiles the platform offers.
+///
+/// Unknown names are skipped, so a newer kernel doesn't break the parsing.
+pub fn choices() -> Result<Vec<PlatformProfile>, PlatformProfileError> {
+    Ok(read_attr(CHOICES_PATH)?
+        .split_whitespace()
+        .filter_map(|choice| choice.parse().ok())
+        .collect())
+}
+
+/// Read the current platform profile.
+pub fn read() -> Result<PlatformProfile, PlatformProfileError> {
+    read_attr(PROFILE_PATH)?.parse()
+}
+
+/// Apply the given platform profile, after checking it is one of the [choices].
+pub fn apply(profile: PlatformProfile) -> Result<(), PlatformProfileError> {
+    if !choices()?.contains(&profile) {
+        return Err(PlatformProfileError::NotAChoice { profile });
+    }
+
+    sysfs::write(PROFILE_PATH, profile)
+        .map_err(|error| PlatformProfileError::WriteFailed { profile, error })
+}
+
+/// Interface used by [ProfileController] to apply profiles.
+#[derive(Debug, Clone)]
+pub enum ProfileBackend {
+    /// ACPI platform_profile, visible to the desktop and power-profiles-daemon.
+    PlatformProfile,
+    /// Thermal policy DEV_ID written directly through debugfs.
+    ThermalPolicy(ThermalPolicy),
+}
+
+/// Reads and applies thermal profiles through either interface, so the desktop
+/// indicator and the tools using this crate agree about the current mode.
+#[derive(Debug, Clone)]
+pub struct ProfileController {
+    backend: ProfileBackend,
+}
+
+impl ProfileController {
+    pub const fn new(backend: ProfileBackend) -> Self {
+        ProfileController { backend }
+    }
+
+    /// Prefer the platform_profile interface, falling back to the thermal policy DEV_ID.
+    pub fn detect() -> Result<Self, PlatformProfileError> {
+        if is_available() {
+            Ok(Self::new(ProfileBackend::PlatformProfile))
+        } else {
+            Ok(Self::new(ProfileBackend::ThermalPolicy(
+                ThermalPolicy::detect()?,
+            )))
+        }
+    }
+
+    pub const fn backend(&self) -> &ProfileBackend {
+        &self.backend
+    }
+
+    /// Read the current thermal profile.
+    pub fn read(&self) -> Result<ThermalProfile, PlatformProfileError> {
+        match &self.backend {
+            ProfileBackend::PlatformProfile => {
+                let profile = read()?;
+                profile
+                    .thermal_profile()
+                    .ok_or(PlatformProfileError::NoMatchingThermalProfile { profile })
+            }
+            ProfileBackend::ThermalPolicy(policy) => Ok(policy.read()?),
+        }
+    }
+
+    /// Apply the given thermal profile.
+    pub fn apply(&self, profile: ThermalProfile) -> Result<(), PlatformProfileError> {
+        match &self.backend {
+            ProfileBackend::PlatformProfile => {
+                let choice = PlatformProfile::from_thermal_profile(profile, &choices()?)
+                    .ok_or(PlatformProfileError::NoMatchingChoice { profile })?;
+                apply(choice)
+            }
+            ProfileBackend::ThermalPolicy(policy) => Ok(policy.apply(profile)?),
+        }
+    }
+
+    /// Read the current fan mode.
+    pub fn read_fan_mode(&self) -> Result<FanMode, PlatformProfileError> {
+        Ok(self.read()?.into())
+    }
+
+    /// Apply the given fan mode.
+    pub fn apply_fan_mode(&self, mode: FanMode) -> Result<(), PlatformProfileError> {
+        self.apply(mode.into())
+    }
+}
+
+#[test]
+fn profile_names_roundtrip() {
+    for profile in [
+        PlatformProfile::LowPower,
+        PlatformProfile::Cool,
+        PlatformProfile::Quiet,
+        PlatformProfile::Balanced,
+        PlatformProfile::BalancedPerformance,
+        PlatformProfile::Performance,
+        PlatformProfile::Custom,
+    ] {
+        assert_eq!(
+            profile.as_str().parse::<PlatformProfile>().unwrap(),
+            profile
+        );
+    }
+
+    assert!("turbo".parse::<PlatformProfile>().is_err());
+}
+
+#[test]
+fn thermal_profile_choice() {
+    let choices = [
+        PlatformProfile::LowPower,
+        PlatformProfile::Balanced,
+        PlatformProfile::Performance,
+    ];
+
+    assert_eq!(
+        PlatformProfile::from_thermal_profile(ThermalProfile::Quiet, &choices),
+        Some(PlatformProfile::LowPower)
+    );
+    assert_eq!(
+        PlatformProfile::from_thermal_profile(ThermalProfile::FullSpeed, &choices),
+        Some(PlatformProfile::Performance)
+    );
+    assert_eq!(
+        PlatformProfile::from_thermal_profile(ThermalProfile::Balanced, &[]),
+        None
+    );
+}
+
+#[test]
+fn platform_profile() {
+    let initial_profile = read().expect("there should be a current platform profile");
+
+    for choice in choices().expect("there should be platform profile choices") {
+        apply(choice).expect("platform profile should be applied");
+        assert_eq!(read().unwrap(), choice);
+    }
+
+    // return to initial profile
+    apply(initial_profile).expect("platform profile should be switched to initial profile");
+    assert_eq!(
+        read().unwrap(),
+        initial_profile,
+        "Failed to revert to initial profile"
+    );
+}
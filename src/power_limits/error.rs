@@ -0,0 +1,51 @@
+//! Error types and messages for the power_limits module.
+
+use super::PowerLimit;
+use crate::debugfs::error::HardwareError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PowerLimitsError {
+    #[error("`{limit}` is not supported on this machine!")]
+    Unsupported { limit: PowerLimit },
+
+    #[error("`{limit}` cannot be read back on this machine!")]
+    NotReadable { limit: PowerLimit },
+
+    #[error(
+        "`{limit}` value `{value}` is out of range, expected a value between {min} and {max}!"
+    )]
+    OutOfRange {
+        limit: PowerLimit,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+
+    #[error("`{lower}` ({lower_value}) must not exceed `{upper}` ({upper_value})!")]
+    InvalidOrder {
+        lower: PowerLimit,
+        lower_value: u32,
+        upper: PowerLimit,
+        upper_value: u32,
+    },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error("Failed to write `{limit}`! {error}")]
+    WriteFailed {
+        limit: PowerLimit,
+        error: std::io::Error,
+    },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
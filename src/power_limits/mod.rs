@@ -0,0 +1,361 @@
+//! Package power tracking (PPT) limits and NVIDIA dynamic boost controls.
+//!
+//! ROG and TUF models expose the limits through the `asus-armoury` firmware attributes
+//! (with the model's min/max), the asus-nb-wmi platform attributes, and their DEV_IDs.
+//! The first interface found is used for each limit.
+//!
+//! [PowerLimits] reads all the limits at once, validates a set against the model ranges
+//! and the relative constraints (SPL ≤ SPPT ≤ FPPT), and applies it as one unit, so it can
+//! be used to ship tuned power presets.
+
+pub mod error;
+
+use crate::{debugfs::Hardware, sysfs};
+use error::PowerLimitsError;
+use std::{fmt, io::ErrorKind, path::PathBuf};
+
+const FIRMWARE_ATTRIBUTES_PATH: &str = "/sys/class/firmware-attributes/asus-armoury/attributes";
+const PLATFORM_PATH: &str = "/sys/devices/platform/asus-nb-wmi";
+
+/// A single power limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerLimit {
+    /// Sustained package power limit (SPL), in watts.
+    PptPl1Spl,
+    /// Slow package power tracking limit (SPPT), in watts.
+    PptPl2Sppt,
+    /// Fast package power tracking limit (FPPT), in watts.
+    PptFppt,
+    /// APU SPPT, in watts.
+    PptApuSppt,
+    /// Platform SPPT, in watts.
+    PptPlatformSppt,
+    /// NVIDIA dynamic boost, in watts.
+    NvDynamicBoost,
+    /// NVIDIA GPU temperature target, in °C.
+    NvTempTarget,
+}
+
+impl PowerLimit {
+    pub const ALL: [PowerLimit; 7] = [
+        PowerLimit::PptPl1Spl,
+        PowerLimit::PptPl2Sppt,
+        PowerLimit::PptFppt,
+        PowerLimit::PptApuSppt,
+        PowerLimit::PptPlatformSppt,
+        PowerLimit::NvDynamicBoost,
+        PowerLimit::NvTempTarget,
+    ];
+
+    pub const fn dev_id(self) -> u64 {
+        match self {
+            PowerLimit::PptPl2Sppt => 0x001200A0,
+            PowerLimit::PptPl1Spl => 0x001200A3,
+            PowerLimit::PptApuSppt => 0x001200B0,
+            PowerLimit::PptPlatformSppt => 0x001200B1,
+            PowerLimit::NvDynamicBoost => 0x001200C0,
+            PowerLimit::PptFppt => 0x001200C1,
+            PowerLimit::NvTempTarget => 0x001200C2,
+        }
+    }
+
+    /// Name of the sysfs attribute.
+    pub const fn name(self) -> &'static str {
+        match self {
+            PowerLimit::PptPl1Spl => "ppt_pl1_spl",
+            PowerLimit::PptPl2Sppt => "ppt_pl2_sppt",
+            PowerLimit::PptFppt => "ppt_fppt",
+            PowerLimit::PptApuSppt => "ppt_apu_sppt",
+            PowerLimit::PptPlatformSppt => "ppt_platform_sppt",
+            PowerLimit::NvDynamicBoost => "nv_dynamic_boost",
+            PowerLimit::NvTempTarget => "nv_temp_target",
+        }
+    }
+
+    /// Range accepted by the asus-wmi driver, used when the firmware doesn't report
+    /// the model's own range.
+    pub const fn default_range(self) -> (u32, u32) {
+        match self {
+            PowerLimit::PptPl1Spl | PowerLimit::PptPl2Sppt | PowerLimit::PptFppt => (5, 250),
+            // PPT_CPU_MIN..=PPT_CPU_MAX in asus-wmi
+            PowerLimit::PptApuSppt | PowerLimit::PptPlatformSppt => (5, 130),
+            PowerLimit::NvDynamicBoost => (5, 25),
+            PowerLimit::NvTempTarget => (75, 87),
+        }
+    }
+
+    pub const fn get(self) -> Hardware<u32> {
+        Hardware::new(self.dev_id())
+    }
+
+    fn firmware_attribute(self, name: &str) -> PathBuf {
+        PathBuf::from(FIRMWARE_ATTRIBUTES_PATH)
+            .join(self.name())
+            .join(name)
+    }
+
+    fn platform_attribute(self) -> PathBuf {
+        PathBuf::from(PLATFORM_PATH).join(self.name())
+    }
+
+    /// Range of the limit on this model, `(min, max)`.
+    pub fn range(self) -> (u32, u32) {
+        let (default_min, default_max) = self.default_range();
+        let read = |name| read_u32(self.firmware_attribute(name)).ok();
+
+        (
+            read("min_value").unwrap_or(default_min),
+            read("max_value").unwrap_or(default_max),
+        )
+    }
+
+    /// Whether the limit can be set on this machine.
+    pub fn is_supported(self) -> Result<bool, PowerLimitsError> {
+        Ok(self.firmware_attribute("current_value").exists()
+            || self.platform_attribute().exists()
+            || self.get().is_present()?)
+    }
+
+    /// Read the current value of the limit.
+    ///
+    /// Only the sysfs interfaces can report it, the DEV_ID being write only.
+    pub fn read(self) -> Result<u32, PowerLimitsError> {
+        for path in [
+            self.firmware_attribute("current_value"),
+            self.platform_attribute(),
+        ] {
+            match read_u32(path) {
+                Err(PowerLimitsError::ReadFailed { error, .. })
+                    if error.kind() == ErrorKind::NotFound => {}
+                result => return result,
+            }
+        }
+
+        Err(PowerLimitsError::NotReadable { limit: self })
+    }
+
+    /// Write the limit, after checking it is within the model's range.
+    pub fn write(self, value: u32) -> Result<(), PowerLimitsError> {
+        let (min, max) = self.range();
+        if !(min..=max).contains(&value) {
+            return Err(PowerLimitsError::OutOfRange {
+                limit: self,
+                value,
+                min,
+                max,
+            });
+        }
+
+        for path in [
+            self.firmware_attribute("current_value"),
+            self.platform_attribute(),
+        ] {
+            if path.exists() {
+                return sysfs::write(path, value)
+                    .map_err(|error| PowerLimitsError::WriteFailed { limit: self, error });
+            }
+        }
+
+        let hardware = self.get();
+        if !hardware.is_present()? {
+            return Err(PowerLimitsError::Unsupported { limit: self });
+        }
+        Ok(hardware.apply(value)?)
+    }
+}
+
+impl fmt::Display for PowerLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn read_u32(path: PathBuf) -> Result<u32, PowerLimitsError> {
+    let value = sysfs::read_trimmed(&path).map_err(|error| PowerLimitsError::ReadFailed {
+        path: path.clone(),
+        error,
+    })?;
+    value
+        .parse()
+        .map_err(|_| PowerLimitsError::UnexpectedValue { path, value })
+}
+
+/// A set of power limits, `None` leaving the limit untouched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PowerLimits {
+    pub ppt_pl1_spl: Option<u32>,
+    pub ppt_pl2_sppt: Option<u32>,
+    pub ppt_fppt: Option<u32>,
+    pub ppt_apu_sppt: Option<u32>,
+    pub ppt_platform_sppt: Option<u32>,
+    pub nv_dynamic_boost: Option<u32>,
+    pub nv_temp_target: Option<u32>,
+}
+
+impl PowerLimits {
+    pub fn get(&self, limit: PowerLimit) -> Option<u32> {
+        match limit {
+            PowerLimit::PptPl1Spl => self.ppt_pl1_spl,
+            PowerLimit::PptPl2Sppt => self.ppt_pl2_sppt,
+            PowerLimit::PptFppt => self.ppt_fppt,
+            PowerLimit::PptApuSppt => self.ppt_apu_sppt,
+            PowerLimit::PptPlatformSppt => self.ppt_platform_sppt,
+            PowerLimit::NvDynamicBoost => self.nv_dynamic_boost,
+            PowerLimit::NvTempTarget => self.nv_temp_target,
+        }
+    }
+
+    pub fn set(&mut self, limit: PowerLimit, value: Option<u32>) {
+        let field = match limit {
+            PowerLimit::PptPl1Spl => &mut self.ppt_pl1_spl,
+            PowerLimit::PptPl2Sppt => &mut self.ppt_pl2_sppt,
+            PowerLimit::PptFppt => &mut self.ppt_fppt,
+            PowerLimit::PptApuSppt => &mut self.ppt_apu_sppt,
+            PowerLimit::PptPlatformSppt => &mut self.ppt_platform_sppt,
+            PowerLimit::NvDynamicBoost => &mut self.nv_dynamic_boost,
+            PowerLimit::NvTempTarget => &mut self.nv_temp_target,
+        };
+        *field = value;
+    }
+
+    /// Read all the limits which can be read back on this machine.
+    pub fn read() -> Result<Self, PowerLimitsError> {
+        let mut limits = PowerLimits::default();
+        for limit in PowerLimit::ALL {
+            match limit.read() {
+                Ok(value) => limits.set(limit, Some(value)),
+                Err(PowerLimitsError::NotReadable { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(limits)
+    }
+
+    /// Check the set against the given ranges and the relative constraints.
+    pub fn validate_with(
+        &self,
+        range: impl Fn(PowerLimit) -> (u32, u32),
+    ) -> Result<(), PowerLimitsError> {
+        for limit in PowerLimit::ALL {
+            let Some(value) = self.get(limit) else {
+                continue;
+            };
+            let (min, max) = range(limit);
+            if !(min..=max).contains(&value) {
+                return Err(PowerLimitsError::OutOfRange {
+                    limit,
+                    value,
+                    min,
+                    max,
+                });
+            }
+        }
+
+        for (lower, upper) in [
+            (PowerLimit::PptPl1Spl, PowerLimit::PptPl2Sppt),
+            (PowerLimit::PptPl2Sppt, PowerLimit::PptFppt),
+            (PowerLimit::PptPl1Spl, PowerLimit::PptFppt),
+        ] {
+            if let (Some(lower_value), Some(upper_value)) = (self.get(lower), self.get(upper)) {
+                if lower_value > upper_value {
+                    return Err(PowerLimitsError::InvalidOrder {
+                        lower,
+                        lower_value,
+                        upper,
+                        upper_value,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the set against the model's ranges and the relative constraints.
+    pub fn validate(&self) -> Result<(), PowerLimitsError> {
+        self.validate_with(PowerLimit::range)
+    }
+
+    /// Validate and apply the set as one unit.
+    ///
+    /// The PPT limits are written in the order keeping SPL ≤ SPPT ≤ FPPT at every step,
+    /// and if any write fails the limits already written are reverted to their previous
+    /// values.
+    pub fn apply(&self) -> Result<(), PowerLimitsError> {
+        self.validate()?;
+
+        let previous = PowerLimits::read()?;
+
+        let raising = match (self.ppt_pl1_spl, previous.ppt_pl1_spl) {
+            (Some(new), Some(old)) => new > old,
+            _ => true,
+        };
+        let mut order = [
+            PowerLimit::PptFppt,
+            PowerLimit::PptPl2Sppt,
+            PowerLimit::PptPl1Spl,
+            PowerLimit::PptApuSppt,
+            PowerLimit::PptPlatformSppt,
+            PowerLimit::NvDynamicBoost,
+            PowerLimit::NvTempTarget,
+        ];
+        if !raising {
+            order[..3].reverse();
+        }
+
+        let mut written = Vec::new();
+        for limit in order {
+            let Some(value) = self.get(limit) else {
+                continue;
+            };
+            if let Err(e) = limit.write(value) {
+                // best effort, the original error is the one worth reporting
+                for limit in written.into_iter().rev() {
+                    if let Some(value) = previous.get(limit) {
+                        let _ = limit.write(value);
+                    }
+                }
+                return Err(e);
+            }
+            written.push(limit);
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn validate_relative_constraints() {
+    let range = PowerLimit::default_range;
+
+    let limits = PowerLimits {
+        ppt_pl1_spl: Some(45),
+        ppt_pl2_sppt: Some(65),
+        ppt_fppt: Some(80),
+        ..Default::default()
+    };
+    assert!(limits.validate_with(range).is_ok());
+
+    let limits = PowerLimits {
+        ppt_pl1_spl: Some(70),
+        ppt_fppt: Some(65),
+        ..Default::default()
+    };
+    assert!(matches!(
+        limits.validate_with(range),
+        Err(PowerLimitsError::InvalidOrder {
+            lower: PowerLimit::PptPl1Spl,
+            upper: PowerLimit::PptFppt,
+            ..
+        })
+    ));
+
+    let limits = PowerLimits {
+        nv_temp_target: Some(95),
+        ..Default::default()
+    };
+    assert!(matches!(
+        limits.validate_with(range),
+        Err(PowerLimitsError::OutOfRange { value: 95, .. })
+    ));
+}
@@ -0,0 +1,55 @@
+//! Error types and messages for the controller module.
+
+use crate::pwm::{
+    fan_curve::error::FanCurveError,
+    pwm_enable::error::{DutySetError, FanModeSetError, PwmEnableError},
+};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ControllerError {
+    #[error("No temperature sensor given to the fan controller!")]
+    NoSensors,
+
+    #[error("No fan given to the fan controller!")]
+    NoFans,
+
+    #[error("No temperature sensor labelled `{label}` found!")]
+    SensorNotFound { label: String },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error(
+        "Temperature couldn't be read {failures} times in a row, fans reverted to Auto! {error}"
+    )]
+    SensorFailed {
+        failures: u32,
+        error: Box<ControllerError>,
+    },
+
+    #[error("Failed to switch pwm{pwm_id} back to Auto, it may be stuck at a low duty! {error}")]
+    RevertFailed { pwm_id: u8, error: FanModeSetError },
+
+    #[error("Failed to install the signal handlers! {error}")]
+    SignalSetupFailed { error: std::io::Error },
+
+    #[error("{error}")]
+    FanModeSetError { error: FanModeSetError },
+
+    #[error("{error}")]
+    DutySetError { error: DutySetError },
+
+    #[error("{error}")]
+    PwmEnableError { error: PwmEnableError },
+
+    #[error("{0}")]
+    FanCurveError(#[from] FanCurveError),
+}
@@ -0,0 +1,412 @@
+//! Userspace fan curve controller, for models without firmware custom curves.
+//!
+//! Reads temperature sensors, computes the duty from a [FanCurve] and writes it in
+//! [Manual](AsusNbWmiFanMode::Manual) mode. The fans are switched back to
+//! [Auto](AsusNbWmiFanMode::Auto) whenever the controller stops: when the worker is
+//! stopped or dropped, when the loop panics, when the process gets a termination
+//! signal, and when the sensors fail to read repeatedly. Only a `panic = "abort"`
+//! build or `SIGKILL` can bypass it. Fans whose hwmon device is renumbered, such as
+//! after reloading `asus_nb_wmi`, are re-opened and switched to Manual again.
+//!
+//! The signal handlers are process wide, so run a single controller per process
+//! and give it all the fans.
+
+pub mod error;
+mod signal;
+
+use crate::{
+    pwm::{
+        fan::AsusNbWmiFanMode,
+        fan_curve::FanCurve,
+        hwmon,
+        pwm_enable::{
+            traits::{ReadConfig, WriteConfig},
+            PwmEnable, PwmEnableReadWrite,
+        },
+    },
+    sysfs,
+    worker::{StopSignal, Worker},
+};
+use error::ControllerError;
+use signal::SignalGuard;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// How often a termination signal is checked for while waiting between updates.
+const SIGNAL_POLL: Duration = Duration::from_millis(100);
+
+/// Temperature from a hwmon `temp*_input` file.
+#[derive(Debug, Clone)]
+pub struct TempSensor {
+    path: PathBuf,
+}
+
+impl TempSensor {
+    /// Use the given `temp*_input` file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        TempSensor { path: path.into() }
+    }
+
+    /// Find the sensor whose `temp*_label` is `label` (such as `Tctl` or `Package id 0`),
+    /// in any hwmon device.
+    pub fn find(label: &str) -> Result<Self, ControllerError> {
+        let hwmons = hwmon::all().map_err(|error| ControllerError::ReadFailed {
+            path: hwmon::HWMON_PATH.into(),
+            error,
+        })?;
+
+        for path in hwmons {
+            let Ok(files) = fs::read_dir(&path) else {
+                continue;
+            };
+            for file in files.filter_map(|entry| entry.ok()) {
+                let name = file.file_name();
+                let Some(prefix) = name.to_str().and_then(|n| n.strip_suffix("_label")) else {
+                    continue;
+                };
+                if prefix.starts_with("temp")
+                    && sysfs::read_trimmed(file.path()).is_ok_and(|l| l == label)
+                {
+                    return Ok(TempSensor::new(path.join(format!("{prefix}_input"))));
+                }
+            }
+        }
+
+        Err(ControllerError::SensorNotFound {
+            label: label.to_string(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the temperature in °C.
+    pub fn read(&self) -> Result<f32, ControllerError> {
+        let value =
+            sysfs::read_trimmed(&self.path).map_err(|error| ControllerError::ReadFailed {
+                path: self.path.clone(),
+                error,
+            })?;
+        let millidegrees: i32 = value
+            .parse()
+            .map_err(|_| ControllerError::UnexpectedValue {
+                path: self.path.clone(),
+                value,
+            })?;
+        Ok(millidegrees as f32 / 1000.0)
+    }
+}
+
+/// Tuning of the [FanController] loop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControllerSettings {
+    /// Time between updates.
+    pub interval: Duration,
+    /// Drop in °C needed before lowering the duty, so the fan doesn't hunt.
+    pub hysteresis: f32,
+    /// Largest increase of the duty per update.
+    pub max_step_up: u8,
+    /// Largest decrease of the duty per update.
+    pub max_step_down: u8,
+    /// Failed sensor reads in a row before giving up.
+    pub max_failures: u32,
+}
+
+impl Default for ControllerSettings {
+    fn default() -> Self {
+        ControllerSettings {
+            interval: Duration::from_secs(1),
+            hysteresis: 3.0,
+            max_step_up: 40,
+            max_step_down: 8,
+            max_failures: 3,
+        }
+    }
+}
+
+/// Duty for `temp` on the curve, interpolated linearly between the points.
+fn duty_for(curve: &FanCurve, temp: f32) -> u8 {
+    let points = &curve.points;
+    if temp <= f32::from(points[0].temp) {
+        return points[0].duty;
+    }
+
+    for pair in points.windows(2) {
+        let (low, high) = (pair[0], pair[1]);
+        if temp <= f32::from(high.temp) {
+            let ratio = (temp - f32::from(low.temp)) / f32::from(high.temp - low.temp);
+            let duty = f32::from(low.duty) + ratio * (f32::from(high.duty) - f32::from(low.duty));
+            return duty.round() as u8;
+        }
+    }
+
+    points[points.len() - 1].duty
+}
+
+/// Temperature to follow, holding `last` while within `hysteresis` below it.
+fn follow_temp(last: Option<f32>, temp: f32, hysteresis: f32) -> f32 {
+    match last {
+        Some(last) if temp < last && temp > last - hysteresis => last,
+        _ => temp,
+    }
+}
+
+/// Move from `current` towards `target` by at most the given steps.
+fn ramp(current: u8, target: u8, max_step_up: u8, max_step_down: u8) -> u8 {
+    if target > current {
+        current.saturating_add(max_step_up).min(target)
+    } else {
+        current.saturating_sub(max_step_down).max(target)
+    }
+}
+
+/// A fan driven by the controller, with its state between updates.
+struct ControlledFan {
+    fan: PwmEnable<PwmEnableReadWrite>,
+    curve: FanCurve,
+    temp: Option<f32>,
+    duty: u8,
+}
+
+/// Fans in Manual mode, to be switched back to Auto with [revert_all](AutoRevert::revert_all).
+///
+/// Dropping it without reverting, such as while unwinding from a panic, still tries
+/// to switch the fans back as a last resort.
+struct AutoRevert {
+    fans: Vec<ControlledFan>,
+    reverted: bool,
+}
+
+impl AutoRevert {
+    /// Switch every fan back to Auto, returning the first failure after trying all of them.
+    fn revert_all(&mut self) -> Result<(), ControllerError> {
+        self.reverted = true;
+
+        let mut result = Ok(());
+        for controlled in &mut self.fans {
+            if let Err(error) = controlled.fan.set_fan_mode(AsusNbWmiFanMode::Auto) {
+                if result.is_ok() {
+                    result = Err(ControllerError::RevertFailed {
+                        pwm_id: controlled.fan.pwm_id,
+                        error,
+                    });
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Drop for AutoRevert {
+    fn drop(&mut self) {
+        if !self.reverted {
+            // nowhere to report the failure while unwinding
+            let _ = self.revert_all();
+        }
+    }
+}
+
+/// Drives fans in Manual mode from temperature sensors and per fan curves.
+///
+/// ```no_run
+/// use meh_asus::pwm::controller::{FanController, TempSensor};
+/// use meh_asus::pwm::fan_curve::FanCurve;
+/// use meh_asus::pwm::pwm_enable::{traits::PwmHardware, PwmEnable, PwmEnableReadWrite};
+///
+/// let curve = FanCurve::from_pairs([
+///     (40, 0), (50, 40), (60, 70), (70, 110), (75, 150), (80, 190), (85, 230), (90, 255),
+/// ]);
+/// let cpu_fan: PwmEnable<PwmEnableReadWrite> = PwmEnable::find_and_get(1).unwrap();
+///
+/// let worker = FanController::new(vec![TempSensor::find("Tctl").unwrap()])
+///     .fan(cpu_fan, curve)
+///     .unwrap()
+///     .start()
+///     .unwrap();
+/// // fans are reverted to Auto once the worker is stopped or dropped
+/// worker.join();
+/// ```
+pub struct FanController {
+    sensors: Vec<TempSensor>,
+    fans: Vec<(PwmEnable<PwmEnableReadWrite>, FanCurve)>,
+    pub settings: ControllerSettings,
+}
+
+impl FanController {
+    /// Controller following the hottest of the given sensors.
+    pub fn new(sensors: Vec<TempSensor>) -> Self {
+        FanController {
+            sensors,
+            fans: Vec::new(),
+            settings: ControllerSettings::default(),
+        }
+    }
+
+    pub fn with_settings(mut self, settings: ControllerSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Drive `fan` with the given curve.
+    pub fn fan(
+        mut self,
+        fan: PwmEnable<PwmEnableReadWrite>,
+        curve: FanCurve,
+    ) -> Result<Self, ControllerError> {
+        curve.validate()?;
+        self.fans.push((fan, curve));
+        Ok(self)
+    }
+
+    /// Switch the fans to Manual mode and start the loop on a background thread.
+    pub fn start(self) -> Result<Worker<Result<(), ControllerError>>, ControllerError> {
+        if self.sensors.is_empty() {
+            return Err(ControllerError::NoSensors);
+        }
+        if self.fans.is_empty() {
+            return Err(ControllerError::NoFans);
+        }
+
+        let signals =
+            SignalGuard::install().map_err(|error| ControllerError::SignalSetupFailed { error })?;
+
+        let mut revert = AutoRevert {
+            fans: Vec::new(),
+            reverted: false,
+        };
+        for (mut fan, curve) in self.fans {
+            // the current duty is only the starting point of the ramp
+            let duty = fan.get_duty().unwrap_or(u8::MAX);
+            if let Err(error) = fan.set_fan_mode(AsusNbWmiFanMode::Manual) {
+                revert.revert_all()?;
+                return Err(ControllerError::FanModeSetError { error });
+            }
+            revert.fans.push(ControlledFan {
+                fan,
+                curve,
+                temp: None,
+                duty,
+            });
+        }
+
+        let (sensors, settings) = (self.sensors, self.settings);
+        Ok(Worker::spawn(move |stop| {
+            let result = run(&sensors, settings, &mut revert, &stop);
+            let reverted = revert.revert_all();
+
+            if let Some(signal) = SignalGuard::received() {
+                signals.reraise(signal);
+            }
+            // a fan left in Manual mode matters more than why the loop stopped
+            reverted.and(result)
+        }))
+    }
+}
+
+/// Hottest temperature of the sensors.
+fn hottest(sensors: &[TempSensor]) -> Result<f32, ControllerError> {
+    let mut hottest = f32::MIN;
+    for sensor in sensors {
+        hottest = hottest.max(sensor.read()?);
+    }
+    Ok(hottest)
+}
+
+/// Update the fans until stopped, a signal arrives or an error occurs.
+fn run(
+    sensors: &[TempSensor],
+    settings: ControllerSettings,
+    revert: &mut AutoRevert,
+    stop: &StopSignal,
+) -> Result<(), ControllerError> {
+    let mut failures = 0;
+
+    loop {
+        match hottest(sensors) {
+            Ok(temp) => {
+                failures = 0;
+                for controlled in &mut revert.fans {
+                    // the driver resets the fan to Auto when reloaded
+                    let reattached = controlled
+                        .fan
+                        .ensure_attached()
+                        .map_err(|error| ControllerError::PwmEnableError { error })?;
+                    if reattached {
+                        controlled
+                            .fan
+                            .set_fan_mode(AsusNbWmiFanMode::Manual)
+                            .map_err(|error| ControllerError::FanModeSetError { error })?;
+                        controlled.temp = None;
+                    }
+
+                    let temp = follow_temp(controlled.temp, temp, settings.hysteresis);
+                    let duty = ramp(
+                        controlled.duty,
+                        duty_for(&controlled.curve, temp),
+                        settings.max_step_up,
+                        settings.max_step_down,
+                    );
+                    if duty != controlled.duty || controlled.temp.is_none() {
+                        controlled
+                            .fan
+                            .set_duty(duty)
+                            .map_err(|error| ControllerError::DutySetError { error })?;
+                    }
+                    controlled.temp = Some(temp);
+                    controlled.duty = duty;
+                }
+            }
+            Err(error) => {
+                failures += 1;
+                if failures >= settings.max_failures {
+                    return Err(ControllerError::SensorFailed {
+                        failures,
+                        error: Box::new(error),
+                    });
+                }
+            }
+        }
+
+        let next = Instant::now() + settings.interval;
+        while let Some(left) = next.checked_duration_since(Instant::now()) {
+            if stop.sleep(left.min(SIGNAL_POLL)) {
+                return Ok(());
+            }
+            if SignalGuard::received().is_some() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[test]
+fn controller_curve_following() {
+    let curve = FanCurve::from_pairs([
+        (40, 0),
+        (50, 40),
+        (60, 80),
+        (70, 120),
+        (75, 160),
+        (80, 200),
+        (85, 230),
+        (90, 255),
+    ]);
+    assert_eq!(duty_for(&curve, 20.0), 0);
+    assert_eq!(duty_for(&curve, 45.0), 20);
+    assert_eq!(duty_for(&curve, 60.0), 80);
+    assert_eq!(duty_for(&curve, 100.0), 255);
+
+    assert_eq!(follow_temp(None, 50.0, 3.0), 50.0);
+    assert_eq!(follow_temp(Some(50.0), 48.0, 3.0), 50.0);
+    assert_eq!(follow_temp(Some(50.0), 46.0, 3.0), 46.0);
+    assert_eq!(follow_temp(Some(50.0), 55.0, 3.0), 55.0);
+
+    assert_eq!(ramp(0, 255, 40, 8), 40);
+    assert_eq!(ramp(100, 120, 40, 8), 120);
+    assert_eq!(ramp(100, 0, 40, 8), 92);
+    assert_eq!(ramp(5, 0, 40, 8), 0);
+}
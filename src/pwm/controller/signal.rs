@@ -0,0 +1,73 @@
+//! Termination signals, noticed by the controller loop so it can revert the fans
+//! before the process goes away.
+
+use std::{
+    io, mem, ptr,
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+const SIGNALS: [libc::c_int; 4] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP, libc::SIGQUIT];
+
+/// Last signal received, `0` if none.
+static RECEIVED: AtomicI32 = AtomicI32::new(0);
+
+extern "C" fn handle(signal: libc::c_int) {
+    RECEIVED.store(signal, Ordering::SeqCst);
+}
+
+/// Installed signal handlers, restoring the previous ones when dropped.
+pub(super) struct SignalGuard {
+    previous: Vec<(libc::c_int, libc::sigaction)>,
+}
+
+impl SignalGuard {
+    pub(super) fn install() -> io::Result<Self> {
+        RECEIVED.store(0, Ordering::SeqCst);
+
+        let mut guard = SignalGuard {
+            previous: Vec::with_capacity(SIGNALS.len()),
+        };
+        for signal in SIGNALS {
+            // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+            unsafe {
+                let mut action: libc::sigaction = mem::zeroed();
+                action.sa_sigaction = handle as *const () as libc::sighandler_t;
+                libc::sigemptyset(&mut action.sa_mask);
+
+                let mut previous: libc::sigaction = mem::zeroed();
+                if libc::sigaction(signal, &action, &mut previous) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                guard.previous.push((signal, previous));
+            }
+        }
+        Ok(guard)
+    }
+
+    pub(super) fn received() -> Option<libc::c_int> {
+        match RECEIVED.load(Ordering::SeqCst) {
+            0 => None,
+            signal => Some(signal),
+        }
+    }
+
+    /// Restore the previous handlers and deliver the signal again to them.
+    pub(super) fn reraise(self, signal: libc::c_int) {
+        drop(self);
+        // SAFETY: raising a signal has no memory safety requirements
+        unsafe {
+            libc::raise(signal);
+        }
+    }
+}
+
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        for (signal, previous) in self.previous.drain(..) {
+            // SAFETY: restores an action previously returned by sigaction
+            unsafe {
+                libc::sigaction(signal, &previous, ptr::null_mut());
+            }
+        }
+    }
+}
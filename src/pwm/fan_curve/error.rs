@@ -0,0 +1,46 @@
+//! Error types and messages for the fan_curve module.
+
+#[cfg(feature = "common-hardware")]
+use crate::platform_profile::error::PlatformProfileError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FanCurveError {
+    #[error("No `asus_custom_fan_curve` hwmon device found, custom fan curves are unsupported!")]
+    NotFound,
+
+    #[error("Fan curve for pwm{pwm_id} is not available on this machine!")]
+    UnsupportedFan { pwm_id: u8 },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to write `{path:?}`! {error}")]
+    WriteFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error("Temperature of point {point} must be higher than the previous point!")]
+    TempNotIncreasing { point: usize },
+
+    #[error("Duty of point {point} must not be lower than the previous point!")]
+    DutyDecreasing { point: usize },
+
+    #[error("Temperature {temp}°C of point {point} exceeds the maximum of {max}°C!")]
+    TempTooHigh { point: usize, temp: u8, max: u8 },
+
+    #[error("Duty {duty} of the last point is below the minimum of {min}!")]
+    FinalDutyTooLow { duty: u8, min: u8 },
+
+    #[cfg(feature = "common-hardware")]
+    #[error("{0}")]
+    PlatformProfileError(#[from] PlatformProfileError),
+}
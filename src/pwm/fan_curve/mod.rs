@@ -0,0 +1,279 @@
+//! Custom fan curves through the `asus_custom_fan_curve` hwmon device.
+//!
+//! Each fan (`pwm1` CPU, `pwm2` GPU, `pwm3` mid) has an 8 point curve of temperature
+//! and duty. The firmware keeps a curve per thermal profile and the hwmon device shows
+//! the one of the active profile, so switch the profile first to edit another one
+//! (refer [apply_for_profile](FanCurveDevice::apply_for_profile)).
+
+pub mod error;
+
+#[cfg(feature = "common-hardware")]
+use crate::{common_hardware::thermal_policy::ThermalProfile, platform_profile::ProfileController};
+use crate::{pwm::hwmon, sysfs};
+use error::FanCurveError;
+use std::path::{Path, PathBuf};
+
+/// Number of points in a fan curve.
+pub const POINTS: usize = 8;
+
+/// A point of the curve, running the fan at `duty` (`0` to `255`) from `temp` °C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CurvePoint {
+    pub temp: u8,
+    pub duty: u8,
+}
+
+/// 8 point fan curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FanCurve {
+    pub points: [CurvePoint; POINTS],
+}
+
+impl FanCurve {
+    /// Highest temperature accepted for a point.
+    pub const MAX_TEMP: u8 = 100;
+    /// Lowest duty accepted for the last point, so the fan always spins up when hot.
+    pub const MIN_FINAL_DUTY: u8 = 77;
+
+    pub const fn new(points: [CurvePoint; POINTS]) -> Self {
+        FanCurve { points }
+    }
+
+    /// Curve from `(temp, duty)` pairs.
+    pub fn from_pairs(pairs: [(u8, u8); POINTS]) -> Self {
+        FanCurve {
+            points: pairs.map(|(temp, duty)| CurvePoint { temp, duty }),
+        }
+    }
+
+    /// Check the temperatures are increasing, the duty never decreases, and the
+    /// limits of [MAX_TEMP](FanCurve::MAX_TEMP) and [MIN_FINAL_DUTY](FanCurve::MIN_FINAL_DUTY).
+    pub fn validate(&self) -> Result<(), FanCurveError> {
+        for (point, CurvePoint { temp, .. }) in self.points.iter().enumerate() {
+            if *temp > Self::MAX_TEMP {
+                return Err(FanCurveError::TempTooHigh {
+                    point,
+                    temp: *temp,
+                    max: Self::MAX_TEMP,
+                });
+            }
+        }
+
+        for (point, pair) in self.points.windows(2).enumerate() {
+            if pair[1].temp <= pair[0].temp {
+                return Err(FanCurveError::TempNotIncreasing { point: point + 1 });
+            }
+            if pair[1].duty < pair[0].duty {
+                return Err(FanCurveError::DutyDecreasing { point: point + 1 });
+            }
+        }
+
+        let duty = self.points[POINTS - 1].duty;
+        if duty < Self::MIN_FINAL_DUTY {
+            return Err(FanCurveError::FinalDutyTooLow {
+                duty,
+                min: Self::MIN_FINAL_DUTY,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Meaning of `pwmN_enable` of the `asus_custom_fan_curve` hwmon device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FanCurveMode {
+    /// Custom curve is used.
+    Custom = 1,
+    /// Firmware default curve is used.
+    Default = 2,
+}
+
+/// Written to `pwmN_enable` to reset the curve to the factory default.
+const RESET: u8 = 3;
+
+/// The `asus_custom_fan_curve` hwmon device.
+#[derive(Debug, Clone)]
+pub struct FanCurveDevice {
+    path: PathBuf,
+}
+
+impl FanCurveDevice {
+    /// Find the hwmon device by its name.
+    pub fn find() -> Result<Self, FanCurveError> {
+        hwmon::find_by_name(hwmon::ASUS_CUSTOM_FAN_CURVE)
+            .map_err(|error| FanCurveError::ReadFailed {
+                path: hwmon::HWMON_PATH.into(),
+                error,
+            })?
+            .into_iter()
+            .next()
+            .map(|path| FanCurveDevice { path })
+            .ok_or(FanCurveError::NotFound)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// PWM IDs of the fans with a custom curve.
+    pub fn fans(&self) -> Vec<u8> {
+        (1..=3)
+            .filter(|pwm_id| self.enable_path(*pwm_id).exists())
+            .collect()
+    }
+
+    fn enable_path(&self, pwm_id: u8) -> PathBuf {
+        self.path.join(format!("pwm{pwm_id}_enable"))
+    }
+
+    fn check_fan(&self, pwm_id: u8) -> Result<(), FanCurveError> {
+        if self.enable_path(pwm_id).exists() {
+            Ok(())
+        } else {
+            Err(FanCurveError::UnsupportedFan { pwm_id })
+        }
+    }
+
+    fn read_u8(&self, name: String) -> Result<u8, FanCurveError> {
+        let path = self.path.join(name);
+        let value = sysfs::read_trimmed(&path).map_err(|error| FanCurveError::ReadFailed {
+            path: path.clone(),
+            error,
+        })?;
+        value
+            .parse()
+            .map_err(|_| FanCurveError::UnexpectedValue { path, value })
+    }
+
+    fn write_u8(&self, name: String, value: u8) -> Result<(), FanCurveError> {
+        let path = self.path.join(name);
+        sysfs::write(&path, value).map_err(|error| FanCurveError::WriteFailed { path, error })
+    }
+
+    /// Read the curve of the fan for the active profile.
+    pub fn read(&self, pwm_id: u8) -> Result<FanCurve, FanCurveError> {
+        self.check_fan(pwm_id)?;
+
+        let mut points = [CurvePoint { temp: 0, duty: 0 }; POINTS];
+        for (i, point) in points.iter_mut().enumerate() {
+            point.temp = self.read_u8(format!("pwm{pwm_id}_auto_point{}_temp", i + 1))?;
+            point.duty = self.read_u8(format!("pwm{pwm_id}_auto_point{}_pwm", i + 1))?;
+        }
+        Ok(FanCurve { points })
+    }
+
+    /// Validate and write the curve of the fan for the active profile.
+    ///
+    /// The curve is only used once enabled with [set_mode](FanCurveDevice::set_mode).
+    pub fn write(&self, pwm_id: u8, curve: &FanCurve) -> Result<(), FanCurveError> {
+        self.check_fan(pwm_id)?;
+        curve.validate()?;
+
+        for (i, point) in curve.points.iter().enumerate() {
+            self.write_u8(format!("pwm{pwm_id}_auto_point{}_temp", i + 1), point.temp)?;
+            self.write_u8(format!("pwm{pwm_id}_auto_point{}_pwm", i + 1), point.duty)?;
+        }
+        Ok(())
+    }
+
+    pub fn mode(&self, pwm_id: u8) -> Result<FanCurveMode, FanCurveError> {
+        self.check_fan(pwm_id)?;
+
+        let name = format!("pwm{pwm_id}_enable");
+        match self.read_u8(name.clone())? {
+            1 => Ok(FanCurveMode::Custom),
+            2 => Ok(FanCurveMode::Default),
+            value => Err(FanCurveError::UnexpectedValue {
+                path: self.path.join(name),
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    pub fn set_mode(&self, pwm_id: u8, mode: FanCurveMode) -> Result<(), FanCurveError> {
+        self.check_fan(pwm_id)?;
+        self.write_u8(format!("pwm{pwm_id}_enable"), mode as u8)
+    }
+
+    /// Reset the curve of the fan for the active profile to the factory default,
+    /// which also switches back to [Default](FanCurveMode::Default) mode.
+    pub fn reset(&self, pwm_id: u8) -> Result<(), FanCurveError> {
+        self.check_fan(pwm_id)?;
+        self.write_u8(format!("pwm{pwm_id}_enable"), RESET)
+    }
+
+    /// Switch to `profile`, then write and enable the given `(pwm_id, curve)` pairs for it.
+    ///
+    /// The profile stays active, as the firmware only uses the curves of the active profile.
+    #[cfg(feature = "common-hardware")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "common-hardware")))]
+    pub fn apply_for_profile(
+        &self,
+        controller: &ProfileController,
+        profile: ThermalProfile,
+        curves: &[(u8, FanCurve)],
+    ) -> Result<(), FanCurveError> {
+        for (_, curve) in curves {
+            curve.validate()?;
+        }
+
+        controller.apply(profile)?;
+        for (pwm_id, curve) in curves {
+            self.write(*pwm_id, curve)?;
+            self.set_mode(*pwm_id, FanCurveMode::Custom)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn fan_curve_validation() {
+    let curve = FanCurve::from_pairs([
+        (30, 0),
+        (40, 20),
+        (50, 40),
+        (60, 70),
+        (70, 110),
+        (80, 160),
+        (90, 210),
+        (100, 255),
+    ]);
+    assert!(curve.validate().is_ok());
+
+    let mut bad = curve;
+    bad.points[3].temp = 50;
+    assert!(matches!(
+        bad.validate(),
+        Err(FanCurveError::TempNotIncreasing { point: 3 })
+    ));
+
+    let mut bad = curve;
+    bad.points[5].duty = 100;
+    assert!(matches!(
+        bad.validate(),
+        Err(FanCurveError::DutyDecreasing { point: 5 })
+    ));
+
+    let mut bad = curve;
+    bad.points[7].temp = 120;
+    assert!(matches!(
+        bad.validate(),
+        Err(FanCurveError::TempTooHigh { point: 7, .. })
+    ));
+
+    let bad = FanCurve::from_pairs([
+        (30, 0),
+        (40, 0),
+        (50, 10),
+        (60, 20),
+        (70, 30),
+        (80, 40),
+        (90, 50),
+        (100, 50),
+    ]);
+    assert!(matches!(
+        bad.validate(),
+        Err(FanCurveError::FinalDutyTooLow { duty: 50, .. })
+    ));
+}
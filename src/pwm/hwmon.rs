@@ -0,0 +1,32 @@
+//! Discovery of hwmon devices by their `name`, as the `hwmonN` numbering changes
+//! between boots and kernels.
+
+use std::{fs, io, path::PathBuf};
+
+use crate::sysfs;
+
+pub(crate) const HWMON_PATH: &str = "/sys/class/hwmon";
+
+/// Name of the asus-nb-wmi hwmon device, providing `pwm*_enable` and the fan inputs.
+pub const ASUS: &str = "asus";
+/// Name of the hwmon device providing the custom fan curves.
+pub const ASUS_CUSTOM_FAN_CURVE: &str = "asus_custom_fan_curve";
+
+/// Every `hwmon*` directory, sorted.
+pub fn all() -> io::Result<Vec<PathBuf>> {
+    let mut hwmons: Vec<PathBuf> = fs::read_dir(HWMON_PATH)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("hwmon"))
+        .map(|entry| entry.path())
+        .collect();
+    hwmons.sort();
+    Ok(hwmons)
+}
+
+/// Every `hwmon*` directory whose `name` file is `name`.
+pub fn find_by_name(name: &str) -> io::Result<Vec<PathBuf>> {
+    Ok(all()?
+        .into_iter()
+        .filter(|path| sysfs::read_trimmed(path.join("name")).is_ok_and(|n| n == name))
+        .collect())
+}
@@ -1,4 +1,7 @@
 //! PWM module to control the Fan using PWM configuration.
 
+pub mod controller;
 pub mod fan;
+pub mod fan_curve;
+pub mod hwmon;
 pub mod pwm_enable;
\ No newline at end of file
@@ -13,6 +13,12 @@ pub enum PwmEnableError {
 
     #[error("The action failed with System I/O error! {error}")]
     IOError { error: std::io::Error },
+
+    #[error("No hwmon device named `{name}` found!")]
+    HwmonNotFound { name: &'static str },
+
+    #[error("Hardware of `pwm{pwm_id}_enable` went away and couldn't be found again!")]
+    HardwareDetached { pwm_id: u8 },
 }
 
 #[derive(Debug, Error)]
@@ -25,6 +31,9 @@ pub enum InputReadError {
 
     #[error("Error occured while reading the input! {error}")]
     IOReadError { error: std::io::Error },
+
+    #[error("Hardware went away, likely after a driver reload! Reattach with `ensure_attached`. {error}")]
+    HardwareDetached { error: std::io::Error },
 }
 
 #[derive(Debug, Error)]
@@ -34,6 +43,9 @@ pub enum FanModeReadError {
     
     #[error("Error occured while reading the label! {error}")]
     IOReadError { error: std::io::Error },
+
+    #[error("Hardware went away, likely after a driver reload! Reattach with `ensure_attached`. {error}")]
+    HardwareDetached { error: std::io::Error },
 }
 
 #[derive(Debug, Error)]
@@ -43,6 +55,9 @@ pub enum LabelReadError {
 
     #[error("Error occured while reading the label! {error}")]
     IOReadError { error: std::io::Error },
+
+    #[error("Hardware went away, likely after a driver reload! Reattach with `ensure_attached`. {error}")]
+    HardwareDetached { error: std::io::Error },
 }
 
 #[derive(Debug, Error)]
@@ -55,4 +70,46 @@ pub enum FanModeSetError {
     
     #[error("OS rejected fan mode switch. AC power maybe required to set the fan mode! {error}")]
     AcPowerRequired { error: std::io::Error },
+
+    #[error("{error}")]
+    ReattachFailed { error: PwmEnableError },
+}
+
+#[derive(Debug, Error)]
+pub enum DutyReadError {
+    #[error("Duty couldn't be interpreted as a number! {parse_error}")]
+    NonNumericDutyValue { parse_error: ParseIntError },
+
+    #[error("Duty couldn't be accessed, manual duty may not be supported! {error}")]
+    DutyIncompatible { error: std::io::Error },
+
+    #[error("Error occured while reading the duty! {error}")]
+    IOReadError { error: std::io::Error },
+
+    #[error("Hardware went away, likely after a driver reload! Reattach with `ensure_attached`. {error}")]
+    HardwareDetached { error: std::io::Error },
+}
+
+#[derive(Debug, Error)]
+pub enum DutySetError {
+    #[error("Fan is in `{mode}` mode, switch to `Manual` before setting the duty!")]
+    ManualModeRequired { mode: AsusNbWmiFanMode },
+
+    #[error("Manual duty is not supported for `pwm{pwm_id}` on this platform! {error}")]
+    ManualDutyUnsupported { pwm_id: u8, error: std::io::Error },
+
+    #[error("Percentage `{percent}` is not in the range 0 to 100!")]
+    InvalidPercent { percent: f32 },
+
+    #[error("{error}")]
+    FanModeReadError { error: FanModeReadError },
+
+    #[error("{error}")]
+    DutyReadError { error: DutyReadError },
+
+    #[error("Requested duty couldn't be set! {error}")]
+    IOWriteError { error: std::io::Error },
+
+    #[error("{error}")]
+    ReattachFailed { error: PwmEnableError },
 }
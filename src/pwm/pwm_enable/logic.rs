@@ -1,11 +1,18 @@
 use super::{
-    base_path::BASE_PATH,
-    error::{FanModeReadError, FanModeSetError, InputReadError, LabelReadError, PwmEnableError},
+    error::{
+        DutyReadError, DutySetError, FanModeReadError, FanModeSetError, InputReadError,
+        LabelReadError, PwmEnableError,
+    },
     traits::{PwmEnableState, PwmHardware, ReadConfig, WriteConfig},
 };
-use crate::pwm::fan::AsusNbWmiFanMode;
+use crate::pwm::{fan::AsusNbWmiFanMode, hwmon};
 use std::{
-    ffi::OsString, fs::File, io::Read, marker::PhantomData, os::unix::fs::FileExt, path::Path,
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Read},
+    marker::PhantomData,
+    os::unix::fs::FileExt,
+    path::Path,
 };
 
 /// PWM enable state representation for controlling the pwm_enable hardware.
@@ -13,11 +20,27 @@ pub struct PwmEnableReadWrite;
 /// PWM enable state representation for reading the pwm_enable hardware.
 pub struct PwmEnableReadOnly;
 
+/// A fan re-opened by [ensure_attached](PwmEnable::ensure_attached), after its
+/// hwmon device was renumbered (example: after reloading `asus_nb_wmi`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reattach {
+    pub old_path: OsString,
+    pub new_path: OsString,
+    pub old_pwm_id: u8,
+    pub new_pwm_id: u8,
+}
+
+/// Called with the details whenever a fan is re-opened.
+type ReattachCallback = Box<dyn FnMut(&Reattach) + Send>;
+
 /// PWM enable representation for controlling the pwm_enable hardware.
 pub struct PwmEnable<T: PwmEnableState> {
     pub(crate) file: File,
     pub(crate) path: OsString,
     pub(crate) pwm_id: u8,
+    /// Label at the time of opening, to find the fan again after a renumbering.
+    pub(crate) label: Option<String>,
+    pub(crate) on_reattach: Option<ReattachCallback>,
     pub(crate) _state: PhantomData<T>,
 }
 
@@ -31,10 +54,13 @@ impl<T: PwmEnableState> PwmHardware<T> for PwmEnable<T> {
                 pwm_id,
                 error: e,
             })?;
+        let label = read_label(Path::new(&path), pwm_id).ok();
         Ok(PwmEnable {
             file,
             path,
             pwm_id,
+            label,
+            on_reattach: None,
             _state: PhantomData,
         })
     }
@@ -55,16 +81,150 @@ impl<T: PwmEnableState> PwmHardware<T> for PwmEnable<T> {
 
 impl<T: PwmEnableState> PwmEnable<T> {
     /// Find the path of the hardware for the given PWM ID.
+    ///
+    /// Looks through every hwmon device named `asus`, whatever its `hwmonN` number.
     pub fn find_hwmon_path(pwm_id: u8) -> Result<OsString, PwmEnableError> {
-        let path = Path::new(BASE_PATH);
-
-        (0..=9_u8)
-            .map(|hwmon_id| path.join(format!("hwmon{hwmon_id}/pwm{pwm_id}_enable")))
-            // .inspect(|f| eprintln!("Looking for {f:?}"))
+        hwmon::find_by_name(hwmon::ASUS)
+            .map_err(|e| PwmEnableError::IOError { error: e })?
+            .into_iter()
+            .map(|path| path.join(format!("pwm{pwm_id}_enable")))
             .find(|path| (path.try_exists()).ok().is_some_and(|s| s))
             .map(|s| s.into_os_string())
             .ok_or(PwmEnableError::UnsupportedHardware { pwm_id })
     }
+
+    /// Open every `pwm*_enable` fan of the `asus` hwmon device, ordered by PWM ID.
+    pub fn discover() -> Result<Vec<DiscoveredFan<T>>, PwmEnableError> {
+        let hwmons = hwmon::find_by_name(hwmon::ASUS)
+            .map_err(|e| PwmEnableError::IOError { error: e })?;
+        if hwmons.is_empty() {
+            return Err(PwmEnableError::HwmonNotFound { name: hwmon::ASUS });
+        }
+
+        let mut fans = Vec::new();
+        for path in hwmons {
+            for pwm_id in enable_ids(&path) {
+                let enable = path.join(format!("pwm{pwm_id}_enable"));
+                let fan = Self::new(enable.into_os_string(), pwm_id)?;
+                let label = fan
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| format!("pwm{pwm_id}"));
+                fans.push(DiscoveredFan { label, fan });
+            }
+        }
+
+        fans.sort_by_key(|discovered| discovered.fan.pwm_id);
+        Ok(fans)
+    }
+
+    /// Call `callback` whenever the fan is re-opened by [ensure_attached](PwmEnable::ensure_attached).
+    pub fn on_reattach(&mut self, callback: impl FnMut(&Reattach) + Send + 'static) {
+        self.on_reattach = Some(Box::new(callback));
+    }
+
+    /// Whether the error comes from a handle whose hwmon device went away.
+    fn is_detached(&self, error: &io::Error) -> bool {
+        error.raw_os_error() == Some(libc::ENODEV) || !Path::new(&self.path).exists()
+    }
+
+    /// Check the handle still works, re-opening the fan if its hwmon device went away.
+    ///
+    /// The fan is found again among the `asus` hwmon devices by its label, or by its
+    /// PWM ID when it has no label. Returns `true` if the fan was re-opened, which is
+    /// also reported to the [on_reattach](PwmEnable::on_reattach) callback. The driver
+    /// resets the fan mode when reloaded, so reapply it after a re-attach.
+    pub fn ensure_attached(&mut self) -> Result<bool, PwmEnableError> {
+        match self.file.read_at(&mut [0], 0) {
+            Err(e) if self.is_detached(&e) => {}
+            _ => return Ok(false),
+        }
+
+        let (new_path, new_pwm_id) = self.rediscover()?;
+        let file = File::options()
+            .read(true)
+            .write(T::write_permission())
+            .open(&new_path)
+            .map_err(|e| PwmEnableError::UnableToAccessHardware {
+                pwm_id: new_pwm_id,
+                error: e,
+            })?;
+
+        let reattach = Reattach {
+            old_path: std::mem::replace(&mut self.path, new_path.clone()),
+            new_path,
+            old_pwm_id: self.pwm_id,
+            new_pwm_id,
+        };
+        self.file = file;
+        self.pwm_id = new_pwm_id;
+
+        if let Some(callback) = &mut self.on_reattach {
+            callback(&reattach);
+        }
+        Ok(true)
+    }
+
+    /// Find the path and PWM ID of this fan among the `asus` hwmon devices.
+    fn rediscover(&self) -> Result<(OsString, u8), PwmEnableError> {
+        let detached = || PwmEnableError::HardwareDetached {
+            pwm_id: self.pwm_id,
+        };
+        let hwmons = hwmon::find_by_name(hwmon::ASUS).map_err(|_| detached())?;
+
+        for path in hwmons {
+            let Some(label) = &self.label else {
+                let enable = path.join(format!("pwm{}_enable", self.pwm_id));
+                if enable.exists() {
+                    return Ok((enable.into_os_string(), self.pwm_id));
+                }
+                continue;
+            };
+
+            for pwm_id in enable_ids(&path) {
+                let enable = path.join(format!("pwm{pwm_id}_enable"));
+                if read_label(&enable, pwm_id).is_ok_and(|l| &l == label) {
+                    return Ok((enable.into_os_string(), pwm_id));
+                }
+            }
+        }
+
+        Err(detached())
+    }
+}
+
+/// PWM IDs of the `pwm*_enable` files in the hwmon directory.
+fn enable_ids(hwmon: &Path) -> Vec<u8> {
+    let Ok(entries) = fs::read_dir(hwmon) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.strip_prefix("pwm"))
+                .and_then(|n| n.strip_suffix("_enable"))
+                .and_then(|id| id.parse::<u8>().ok())
+        })
+        .collect()
+}
+
+/// Read `fan{pwm_id}_label` next to the given `pwm*_enable` path.
+fn read_label(enable_path: &Path, pwm_id: u8) -> Result<String, io::Error> {
+    let mut buf = fs::read_to_string(enable_path.with_file_name(format!("fan{pwm_id}_label")))?;
+    if buf.ends_with('\n') {
+        buf.pop();
+    }
+    Ok(buf)
+}
+
+/// A fan found by [discover](PwmEnable::discover), with its label (example: `cpu_fan`).
+pub struct DiscoveredFan<T: PwmEnableState> {
+    pub label: String,
+    pub fan: PwmEnable<T>,
 }
 
 impl<T: PwmEnableState> ReadConfig for PwmEnable<T> {
@@ -72,7 +232,13 @@ impl<T: PwmEnableState> ReadConfig for PwmEnable<T> {
         let mut profile: [u8; 1] = [0];
         self.file
             .read_exact_at(&mut profile, 0)
-            .map_err(|e| FanModeReadError::IOReadError { error: e })?;
+            .map_err(|e| {
+                if self.is_detached(&e) {
+                    FanModeReadError::HardwareDetached { error: e }
+                } else {
+                    FanModeReadError::IOReadError { error: e }
+                }
+            })?;
 
         AsusNbWmiFanMode::try_from(profile[0])
             .map_err(|e| FanModeReadError::AsusNbWmiFanModeError { error: e })
@@ -86,7 +252,13 @@ impl<T: PwmEnableState> ReadConfig for PwmEnable<T> {
             .read(true)
             .write(false)
             .open(label_path)
-            .map_err(|e| LabelReadError::LabelIncompatible { error: e })?;
+            .map_err(|e| {
+                if self.is_detached(&e) {
+                    LabelReadError::HardwareDetached { error: e }
+                } else {
+                    LabelReadError::LabelIncompatible { error: e }
+                }
+            })?;
 
         let mut buf = String::new();
         file.read_to_string(&mut buf)
@@ -107,7 +279,13 @@ impl<T: PwmEnableState> ReadConfig for PwmEnable<T> {
             .read(true)
             .write(false)
             .open(input_path)
-            .map_err(|e| InputReadError::InputIncompatible { error: e })?;
+            .map_err(|e| {
+                if self.is_detached(&e) {
+                    InputReadError::HardwareDetached { error: e }
+                } else {
+                    InputReadError::InputIncompatible { error: e }
+                }
+            })?;
 
         let mut buf = String::new();
         file.read_to_string(&mut buf)
@@ -118,10 +296,40 @@ impl<T: PwmEnableState> ReadConfig for PwmEnable<T> {
         }
         let input = buf
             .parse::<u16>()
-            .map_err(|e| (InputReadError::NonNumericInputValue { parse_error: e }))?;
+            .map_err(|e| InputReadError::NonNumericInputValue { parse_error: e })?;
 
         Ok(input)
     }
+
+    fn get_duty(&self) -> Result<u8, DutyReadError> {
+        let path = Path::new(&self.path);
+        let duty_path = path.with_file_name(format!("pwm{}", self.pwm_id));
+
+        let mut file = File::options()
+            .read(true)
+            .write(false)
+            .open(duty_path)
+            .map_err(|e| {
+                if self.is_detached(&e) {
+                    DutyReadError::HardwareDetached { error: e }
+                } else {
+                    DutyReadError::DutyIncompatible { error: e }
+                }
+            })?;
+
+        let mut buf = String::new();
+        file.read_to_string(&mut buf)
+            .map_err(|e| DutyReadError::IOReadError { error: e })?;
+
+        if buf.ends_with('\n') {
+            buf.pop();
+        }
+        let duty = buf
+            .parse::<u8>()
+            .map_err(|e| DutyReadError::NonNumericDutyValue { parse_error: e })?;
+
+        Ok(duty)
+    }
 }
 
 impl PwmEnable<PwmEnableReadOnly> {
@@ -136,6 +344,8 @@ impl PwmEnable<PwmEnableReadOnly> {
             file,
             path: self.path,
             pwm_id: self.pwm_id,
+            label: self.label,
+            on_reattach: self.on_reattach,
             _state: PhantomData,
         })
     }
@@ -143,6 +353,9 @@ impl PwmEnable<PwmEnableReadOnly> {
 
 impl WriteConfig for PwmEnable<PwmEnableReadWrite> {
     fn set_fan_mode(&mut self, mode: AsusNbWmiFanMode) -> Result<(), FanModeSetError> {
+        self.ensure_attached()
+            .map_err(|e| FanModeSetError::ReattachFailed { error: e })?;
+
         let Err(e) = self.file.write_all_at(&[mode as u8], 0) else {
             return Ok(());
         };
@@ -159,4 +372,47 @@ impl WriteConfig for PwmEnable<PwmEnableReadWrite> {
             _ => FanModeSetError::UnknownError { error: e },
         })
     }
+
+    fn set_duty(&mut self, duty: u8) -> Result<(), DutySetError> {
+        self.ensure_attached()
+            .map_err(|e| DutySetError::ReattachFailed { error: e })?;
+
+        let mode = self
+            .get_fan_mode()
+            .map_err(|e| DutySetError::FanModeReadError { error: e })?;
+        if mode != AsusNbWmiFanMode::Manual {
+            return Err(DutySetError::ManualModeRequired { mode });
+        }
+
+        let path = Path::new(&self.path);
+        let duty_path = path.with_file_name(format!("pwm{}", self.pwm_id));
+
+        let file = File::options()
+            .write(true)
+            .open(duty_path)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => DutySetError::ManualDutyUnsupported {
+                    pwm_id: self.pwm_id,
+                    error: e,
+                },
+                _ => DutySetError::IOWriteError { error: e },
+            })?;
+
+        file.write_all_at(duty.to_string().as_bytes(), 0)
+            .map_err(|e| DutySetError::IOWriteError { error: e })?;
+
+        // asus-wmi accepts the write even where the fan has no manual control
+        let current = self
+            .get_duty()
+            .map_err(|e| DutySetError::DutyReadError { error: e })?;
+        if current != duty {
+            return Err(DutySetError::ManualDutyUnsupported {
+                pwm_id: self.pwm_id,
+                error: std::io::Error::other(format!(
+                    "duty `{current}` was read back after writing `{duty}`"
+                )),
+            });
+        }
+        Ok(())
+    }
 }
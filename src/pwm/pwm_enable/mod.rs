@@ -3,4 +3,4 @@ mod logic;
 mod base_path;
 pub mod traits;
 
-pub use logic::{PwmEnable, PwmEnableReadOnly, PwmEnableReadWrite};
+pub use logic::{DiscoveredFan, PwmEnable, PwmEnableReadOnly, PwmEnableReadWrite, Reattach};
@@ -2,13 +2,21 @@ use crate::pwm::fan::AsusNbWmiFanMode;
 
 use super::{
     base_path::BASE_PATH,
-    error::{FanModeReadError, FanModeSetError, InputReadError, LabelReadError, PwmEnableError},
+    error::{
+        DutyReadError, DutySetError, FanModeReadError, FanModeSetError, InputReadError,
+        LabelReadError, PwmEnableError,
+    },
     PwmEnable, PwmEnableReadOnly, PwmEnableReadWrite,
 };
 
 /// ReadConfig trait is used to read the current configuration of the pwm device
 ///
 /// These doesn't require write permission
+///
+/// Reads don't re-open the fan after its hwmon device went away (example: after reloading
+/// `asus_nb_wmi`), they keep failing with `HardwareDetached` instead. Call
+/// [ensure_attached](PwmEnable::ensure_attached) before reading from a long-lived handle,
+/// the [WriteConfig] methods already do.
 pub trait ReadConfig {
     /// Get the current fan mode
     fn get_fan_mode(&self) -> Result<AsusNbWmiFanMode, FanModeReadError>;
@@ -16,6 +24,8 @@ pub trait ReadConfig {
     fn get_label(&self) -> Result<String, LabelReadError>;
     /// Get the current input value (rpm of fan) of the pwm device
     fn get_input(&self) -> Result<u16, InputReadError>;
+    /// Get the current duty of the fan (`0` to `255`) from `pwm{id}`
+    fn get_duty(&self) -> Result<u8, DutyReadError>;
 }
 
 /// WriteConfig trait is used to write the configuration of the pwm device
@@ -23,6 +33,19 @@ pub trait ReadConfig {
 /// These require write permission
 pub trait WriteConfig {
     fn set_fan_mode(&mut self, mode: AsusNbWmiFanMode) -> Result<(), FanModeSetError>;
+    /// Set the duty of the fan (`0` to `255`)
+    ///
+    /// Refused unless the fan is in [Manual](AsusNbWmiFanMode::Manual) mode
+    fn set_duty(&mut self, duty: u8) -> Result<(), DutySetError>;
+    /// Set the duty of the fan as a percentage (`0.0` to `100.0`)
+    ///
+    /// Refused unless the fan is in [Manual](AsusNbWmiFanMode::Manual) mode
+    fn set_percent(&mut self, percent: f32) -> Result<(), DutySetError> {
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(DutySetError::InvalidPercent { percent });
+        }
+        self.set_duty((percent / 100.0 * 255.0).round() as u8)
+    }
 }
 
 /// PwmHardware trait is used to interact with the pwm device
@@ -67,7 +90,9 @@ pub trait PwmHardware<T: PwmEnableState> {
     where
         Self: PwmEnableState + Sized,
     {
-        let path = format!("{BASE_PATH}/hwmon{hwmon_id}/pwm{pwm_id}_enable").into();
+        let path = std::path::Path::new(BASE_PATH)
+            .join(format!("hwmon{hwmon_id}/pwm{pwm_id}_enable"))
+            .into_os_string();
         Self::new(path, pwm_id)
     }
 
@@ -0,0 +1,32 @@
+//! Error types and messages for the radios module.
+
+use super::Radio;
+use crate::debugfs::error::HardwareError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RadioError {
+    #[error("{radio:?} radio is not present on this machine!")]
+    Unsupported { radio: Radio },
+
+    #[error(
+        "{radio:?} radio is hard blocked (hardware switch or firmware), it cannot be enabled!"
+    )]
+    HardBlocked { radio: Radio },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to write `{path:?}`! {error}")]
+    WriteFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
@@ -0,0 +1,242 @@
+//! Wireless radios (WLAN, Bluetooth, GPS, WiMAX, WWAN and UWB) and the wireless LED.
+//!
+//! asus-wmi registers an rfkill switch for the radios it manages, and the kernel keeps the
+//! radio in step with the rfkill state. So that this module never fights the kernel, a
+//! radio with an rfkill switch is toggled through its soft block, and its DEV_ID is only
+//! written when no rfkill switch manages it. A hard block is always respected.
+
+pub mod error;
+mod rfkill;
+
+pub use rfkill::RfkillDevice;
+
+use crate::{
+    auto_impl_config,
+    common_hardware::led_state::LedState,
+    debugfs::{Config, Hardware},
+    error::StateError,
+    worker::Worker,
+};
+use error::RadioError;
+use std::time::Duration;
+
+/// ASUS_WMI_DEVID_WIRELESS_LED
+pub const WIRELESS_LED_DEV_ID: u64 = 0x00010002;
+
+auto_impl_config!(RadioState, u8, Off = 0, On = 1);
+
+pub const fn wireless_led() -> Hardware<LedState> {
+    Hardware::new(WIRELESS_LED_DEV_ID)
+}
+
+/// Radios known to asus-wmi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Radio {
+    Wlan,
+    Bluetooth,
+    Gps,
+    Wimax,
+    Wwan3g,
+    Uwb,
+}
+
+impl Radio {
+    pub const ALL: [Radio; 6] = [
+        Radio::Wlan,
+        Radio::Bluetooth,
+        Radio::Gps,
+        Radio::Wimax,
+        Radio::Wwan3g,
+        Radio::Uwb,
+    ];
+
+    pub const fn dev_id(self) -> u64 {
+        match self {
+            Radio::Wlan => 0x00010011,
+            Radio::Bluetooth => 0x00010013,
+            Radio::Gps => 0x00010015,
+            Radio::Wimax => 0x00010017,
+            Radio::Wwan3g => 0x00010019,
+            Radio::Uwb => 0x00010021,
+        }
+    }
+
+    /// rfkill type of the radio.
+    pub const fn rfkill_type(self) -> &'static str {
+        match self {
+            Radio::Wlan => "wlan",
+            Radio::Bluetooth => "bluetooth",
+            Radio::Gps => "gps",
+            Radio::Wimax => "wimax",
+            Radio::Wwan3g => "wwan",
+            Radio::Uwb => "uwb",
+        }
+    }
+
+    /// Name of the rfkill switch registered by asus-wmi.
+    pub const fn asus_rfkill_name(self) -> &'static str {
+        match self {
+            Radio::Wlan => "asus-wlan",
+            Radio::Bluetooth => "asus-bluetooth",
+            Radio::Gps => "asus-gps",
+            Radio::Wimax => "asus-wimax",
+            Radio::Wwan3g => "asus-wwan3g",
+            Radio::Uwb => "asus-uwb",
+        }
+    }
+
+    pub const fn get(self) -> Hardware<RadioState> {
+        Hardware::new(self.dev_id())
+    }
+
+    /// rfkill switches of the radio, the asus-wmi one first.
+    pub fn rfkill_devices(self) -> Result<Vec<RfkillDevice>, RadioError> {
+        let mut devices: Vec<RfkillDevice> = RfkillDevice::find_all()?
+            .into_iter()
+            .filter(|device| device.kind == self.rfkill_type())
+            .collect();
+        devices.sort_by_key(|device| device.name != self.asus_rfkill_name());
+        Ok(devices)
+    }
+
+    /// Read the state of the radio, from both the firmware and rfkill.
+    ///
+    /// The firmware state is `None` when debugfs can't be read (such as without root), and
+    /// [Unsupported](RadioError::Unsupported) is only returned when neither source knows the radio.
+    pub fn status(self) -> Result<RadioStatus, RadioError> {
+        let hardware = self.get();
+        // debugfs needs root, fall back to rfkill alone when it can't be read;
+        // never write to find the mask, it would switch the radio off
+        let firmware = match hardware.is_present() {
+            Ok(true) => hardware.read_masked(0x1).ok(),
+            Ok(false) | Err(_) => None,
+        };
+
+        let mut soft_blocked = None;
+        let mut hard_blocked = None;
+        for device in self.rfkill_devices()? {
+            *soft_blocked.get_or_insert(false) |= device.soft_blocked()?;
+            *hard_blocked.get_or_insert(false) |= device.hard_blocked()?;
+        }
+
+        if firmware.is_none() && soft_blocked.is_none() {
+            return Err(RadioError::Unsupported { radio: self });
+        }
+
+        Ok(RadioStatus {
+            radio: self,
+            firmware,
+            soft_blocked,
+            hard_blocked,
+        })
+    }
+
+    /// Enable or disable the radio, through rfkill when it manages the radio.
+    pub fn set_enabled(self, enabled: bool) -> Result<(), RadioError> {
+        let devices = self.rfkill_devices()?;
+
+        if !devices.is_empty() {
+            if enabled {
+                for device in &devices {
+                    if device.hard_blocked()? {
+                        return Err(RadioError::HardBlocked { radio: self });
+                    }
+                }
+            }
+            for device in &devices {
+                device.set_soft_blocked(!enabled)?;
+            }
+            return Ok(());
+        }
+
+        let hardware = self.get();
+        if !hardware.is_present()? {
+            return Err(RadioError::Unsupported { radio: self });
+        }
+        Ok(hardware.apply(if enabled {
+            RadioState::On
+        } else {
+            RadioState::Off
+        })?)
+    }
+
+    /// Radios present on the machine, through either the firmware or rfkill.
+    pub fn available() -> Result<Vec<Radio>, RadioError> {
+        let mut radios = Vec::new();
+        for radio in Radio::ALL {
+            match radio.status() {
+                Ok(_) => radios.push(radio),
+                Err(RadioError::Unsupported { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(radios)
+    }
+}
+
+/// State of a radio, `None` where the interface doesn't know about the radio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadioStatus {
+    pub radio: Radio,
+    /// State reported by the firmware DEV_ID.
+    pub firmware: Option<RadioState>,
+    /// Whether any rfkill switch of the radio is soft blocked.
+    pub soft_blocked: Option<bool>,
+    /// Whether any rfkill switch of the radio is hard blocked.
+    pub hard_blocked: Option<bool>,
+}
+
+impl RadioStatus {
+    /// Whether the radio is usable, rfkill having the final say when it manages the radio.
+    pub fn is_enabled(&self) -> bool {
+        match (self.soft_blocked, self.hard_blocked) {
+            (Some(soft), Some(hard)) => !soft && !hard,
+            _ => self.firmware == Some(RadioState::On),
+        }
+    }
+}
+
+/// Call `on_change` with the status of every available radio whenever any of them
+/// changes, checking every `interval` on a background thread.
+pub fn watch(
+    interval: Duration,
+    mut on_change: impl FnMut(&[RadioStatus]) + Send + 'static,
+) -> Worker<Result<(), RadioError>> {
+    Worker::spawn(move |signal| {
+        let radios = Radio::available()?;
+        let mut last = Vec::new();
+
+        loop {
+            let statuses = radios
+                .iter()
+                .map(|radio| radio.status())
+                .collect::<Result<Vec<_>, _>>()?;
+            if statuses != last {
+                on_change(&statuses);
+                last = statuses;
+            }
+
+            if signal.sleep(interval) {
+                return Ok(());
+            }
+        }
+    })
+}
+
+#[test]
+fn rfkill_has_final_say() {
+    let status = RadioStatus {
+        radio: Radio::Wlan,
+        firmware: Some(RadioState::On),
+        soft_blocked: Some(true),
+        hard_blocked: Some(false),
+    };
+    assert!(!status.is_enabled());
+
+    let status = RadioStatus {
+        soft_blocked: None,
+        hard_blocked: None,
+        ..status
+    };
+    assert!(status.is_enabled());
+}
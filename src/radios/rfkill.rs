@@ -0,0 +1,80 @@
+//! rfkill devices under `/sys/class/rfkill`.
+
+use super::error::RadioError;
+use crate::sysfs;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+const RFKILL_PATH: &str = "/sys/class/rfkill";
+
+/// An rfkill switch registered with the kernel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RfkillDevice {
+    path: PathBuf,
+    /// Name of the switch (example: `asus-wlan`, `phy0`, `hci0`).
+    pub name: String,
+    /// Type of the radio (example: `wlan`, `bluetooth`).
+    pub kind: String,
+}
+
+impl RfkillDevice {
+    /// All the rfkill switches, or none if the class doesn't exist.
+    pub fn find_all() -> Result<Vec<Self>, RadioError> {
+        let entries = match fs::read_dir(RFKILL_PATH) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => {
+                return Err(RadioError::ReadFailed {
+                    path: RFKILL_PATH.into(),
+                    error,
+                })
+            }
+        };
+
+        let mut devices: Vec<Self> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter_map(|path| {
+                Some(RfkillDevice {
+                    name: sysfs::read_trimmed(path.join("name")).ok()?,
+                    kind: sysfs::read_trimmed(path.join("type")).ok()?,
+                    path,
+                })
+            })
+            .collect();
+        devices.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(devices)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_flag(&self, name: &str) -> Result<bool, RadioError> {
+        let path = self.path.join(name);
+        sysfs::read_trimmed(&path)
+            .map(|value| value == "1")
+            .map_err(|error| RadioError::ReadFailed { path, error })
+    }
+
+    /// Blocked by software (such as `rfkill block` or airplane mode).
+    pub fn soft_blocked(&self) -> Result<bool, RadioError> {
+        self.read_flag("soft")
+    }
+
+    /// Blocked by a hardware switch or the firmware, which software cannot undo.
+    pub fn hard_blocked(&self) -> Result<bool, RadioError> {
+        self.read_flag("hard")
+    }
+
+    /// Set the soft block, going through the kernel like `rfkill block`/`unblock` does.
+    pub fn set_soft_blocked(&self, blocked: bool) -> Result<(), RadioError> {
+        let path = self.path.join("soft");
+        sysfs::write(&path, u8::from(blocked))
+            .map_err(|error| RadioError::WriteFailed { path, error })
+    }
+}
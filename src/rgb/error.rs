@@ -0,0 +1,35 @@
+//! Error types and messages for the rgb module.
+
+use crate::debugfs::error::HardwareError;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RgbError {
+    #[error("TUF RGB keyboard is not present on this machine!")]
+    NotFound,
+
+    #[error("Blue `{blue}` cannot be passed through debugfs, which only carries the first argument of the call! Use the LED class backend.")]
+    NotRepresentable { blue: u8 },
+
+    #[error("No effect has been applied through this crate since boot, it cannot be read back!")]
+    Unknown,
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Failed to write `{path:?}`! {error}")]
+    WriteFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
@@ -0,0 +1,304 @@
+//! ASUS_WMI_DEVID_TUF_RGB_MODE and ASUS_WMI_DEVID_TUF_RGB_STATE
+//!
+//! RGB keyboard of TUF laptops, configured with an [RgbEffect] (effect, color and speed)
+//! and [RgbPowerFlags] telling in which power states the keyboard is lit.
+//!
+//! The effect can be applied either through the `kbd_rgb_mode` attribute of the
+//! `asus::kbd_backlight` LED class device, or through debugfs. The debugfs `ctrl_param`
+//! only carries the first argument of the firmware call though, so the blue component
+//! and the speed can only be set through the LED class attribute. Over debugfs, effects
+//! with a blue component are refused and the speed is left to the firmware.
+//!
+//! The firmware doesn't report the effect back, so the last effect applied through this
+//! crate is kept under `/run/meh-asus` to be read back.
+
+pub mod error;
+
+use crate::{debugfs::Hardware, sysfs};
+use error::RgbError;
+use std::{fs, io::ErrorKind, path::PathBuf};
+
+pub const MODE_DEV_ID: u64 = 0x00100056;
+pub const STATE_DEV_ID: u64 = 0x00100057;
+
+const LED_PATH: &str = "/sys/class/leds/asus::kbd_backlight";
+const RUN_DIR: &str = "/run/meh-asus";
+const LAST_EFFECT_PATH: &str = "/run/meh-asus/kbd_rgb_effect";
+
+/// Required default first byte of the state call, nothing happens otherwise.
+const STATE_CALL: u32 = 0xbd;
+
+pub const fn mode() -> Hardware<u32> {
+    Hardware::new(MODE_DEV_ID)
+}
+
+pub const fn state() -> Hardware<u32> {
+    Hardware::new(STATE_DEV_ID)
+}
+
+/// Lighting effects supported across TUF models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RgbMode {
+    Static = 0,
+    Breathe = 1,
+    ColorCycle = 2,
+    Strobe = 10,
+}
+
+impl TryFrom<u8> for RgbMode {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RgbMode::Static),
+            1 => Ok(RgbMode::Breathe),
+            2 => Ok(RgbMode::ColorCycle),
+            10 => Ok(RgbMode::Strobe),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Speed of the animated effects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RgbSpeed {
+    Low = 0,
+    Medium = 1,
+    High = 2,
+}
+
+impl RgbSpeed {
+    /// Value passed to the firmware.
+    pub const fn raw(self) -> u8 {
+        match self {
+            RgbSpeed::Low => 0xe1,
+            RgbSpeed::Medium => 0xeb,
+            RgbSpeed::High => 0xf5,
+        }
+    }
+}
+
+impl TryFrom<u8> for RgbSpeed {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RgbSpeed::Low),
+            1 => Ok(RgbSpeed::Medium),
+            2 => Ok(RgbSpeed::High),
+            _ => Err(value),
+        }
+    }
+}
+
+/// An RGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Rgb { r, g, b }
+    }
+}
+
+/// Effect, color and speed of the keyboard lighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RgbEffect {
+    pub mode: RgbMode,
+    pub color: Rgb,
+    pub speed: RgbSpeed,
+}
+
+impl RgbEffect {
+    pub const fn new(mode: RgbMode, color: Rgb, speed: RgbSpeed) -> Self {
+        RgbEffect { mode, color, speed }
+    }
+
+    /// Arguments of the `kbd_rgb_mode` attribute, `cmd mode r g b speed`.
+    fn to_attribute(self, save: bool) -> String {
+        format!(
+            "{} {} {} {} {} {}",
+            save as u8, self.mode as u8, self.color.r, self.color.g, self.color.b, self.speed as u8
+        )
+    }
+
+    /// The two packed arguments of the firmware call.
+    pub const fn to_raw(self, save: bool) -> (u32, u32) {
+        let cmd: u32 = if save { 0xb4 } else { 0xb3 };
+        (
+            cmd | (self.mode as u32) << 8
+                | (self.color.r as u32) << 16
+                | (self.color.g as u32) << 24,
+            self.color.b as u32 | (self.speed.raw() as u32) << 8,
+        )
+    }
+
+    fn from_attribute(value: &str) -> Option<Self> {
+        let values: Vec<u8> = value
+            .split_whitespace()
+            .map(|v| v.parse().ok())
+            .collect::<Option<_>>()?;
+
+        let [_, mode, r, g, b, speed] = values[..] else {
+            return None;
+        };
+        Some(RgbEffect {
+            mode: mode.try_into().ok()?,
+            color: Rgb::new(r, g, b),
+            speed: speed.try_into().ok()?,
+        })
+    }
+}
+
+/// Power states in which the keyboard is lit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RgbPowerFlags {
+    pub boot: bool,
+    pub awake: bool,
+    pub sleep: bool,
+    pub keyboard: bool,
+}
+
+impl Default for RgbPowerFlags {
+    fn default() -> Self {
+        RgbPowerFlags {
+            boot: true,
+            awake: true,
+            sleep: true,
+            keyboard: true,
+        }
+    }
+}
+
+impl RgbPowerFlags {
+    /// Arguments of the `kbd_rgb_state` attribute, `cmd boot awake sleep keyboard`.
+    fn to_attribute(self, save: bool) -> String {
+        format!(
+            "{} {} {} {} {}",
+            save as u8, self.boot as u8, self.awake as u8, self.sleep as u8, self.keyboard as u8
+        )
+    }
+
+    /// The packed argument of the firmware call.
+    pub const fn to_raw(self, save: bool) -> u32 {
+        let flags = (self.boot as u32) << 1
+            | (self.awake as u32) << 3
+            | (self.sleep as u32) << 5
+            | (self.keyboard as u32) << 7;
+        STATE_CALL | (if save { 1 << 2 } else { 0 }) << 8 | flags << 16
+    }
+}
+
+/// Interface used to drive the RGB keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RgbBackend {
+    /// Attributes of the `asus::kbd_backlight` LED class device.
+    LedClass,
+    /// DEV_IDs through debugfs.
+    Debugfs,
+}
+
+/// TUF RGB keyboard.
+#[derive(Debug, Clone)]
+pub struct RgbKeyboard {
+    backend: RgbBackend,
+}
+
+impl RgbKeyboard {
+    pub const fn new(backend: RgbBackend) -> Self {
+        RgbKeyboard { backend }
+    }
+
+    /// Prefer the LED class attributes, falling back to debugfs.
+    pub fn detect() -> Result<Self, RgbError> {
+        if PathBuf::from(LED_PATH).join("kbd_rgb_mode").exists() {
+            Ok(Self::new(RgbBackend::LedClass))
+        } else if mode().is_present()? {
+            Ok(Self::new(RgbBackend::Debugfs))
+        } else {
+            Err(RgbError::NotFound)
+        }
+    }
+
+    pub const fn backend(&self) -> RgbBackend {
+        self.backend
+    }
+
+    /// Apply the effect, saving it to the firmware when `save` is set so it survives reboot.
+    pub fn apply_effect(&self, effect: RgbEffect, save: bool) -> Result<(), RgbError> {
+        match self.backend {
+            RgbBackend::LedClass => write_attr("kbd_rgb_mode", effect.to_attribute(save))?,
+            RgbBackend::Debugfs => {
+                if effect.color.b != 0 {
+                    return Err(RgbError::NotRepresentable {
+                        blue: effect.color.b,
+                    });
+                }
+                let (arg0, _) = effect.to_raw(save);
+                mode().apply(arg0)?;
+            }
+        }
+
+        // only used to read the effect back, failing to record it is not worth an error
+        let _ = fs::create_dir_all(RUN_DIR)
+            .and_then(|_| sysfs::write(LAST_EFFECT_PATH, effect.to_attribute(save)));
+
+        Ok(())
+    }
+
+    /// Read back the last effect applied through this crate since boot.
+    pub fn read_effect(&self) -> Result<RgbEffect, RgbError> {
+        let value = match sysfs::read_trimmed(LAST_EFFECT_PATH) {
+            Ok(value) => value,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Err(RgbError::Unknown),
+            Err(error) => {
+                return Err(RgbError::ReadFailed {
+                    path: LAST_EFFECT_PATH.into(),
+                    error,
+                })
+            }
+        };
+
+        RgbEffect::from_attribute(&value).ok_or(RgbError::UnexpectedValue {
+            path: LAST_EFFECT_PATH.into(),
+            value,
+        })
+    }
+
+    /// Apply the power state flags, saving them to the firmware when `save` is set.
+    pub fn apply_power_flags(&self, flags: RgbPowerFlags, save: bool) -> Result<(), RgbError> {
+        match self.backend {
+            RgbBackend::LedClass => write_attr("kbd_rgb_state", flags.to_attribute(save)),
+            RgbBackend::Debugfs => Ok(state().apply(flags.to_raw(save))?),
+        }
+    }
+}
+
+fn write_attr(name: &str, value: String) -> Result<(), RgbError> {
+    let path = PathBuf::from(LED_PATH).join(name);
+    sysfs::write(&path, value).map_err(|error| RgbError::WriteFailed { path, error })
+}
+
+#[test]
+fn effect_packing() {
+    let effect = RgbEffect::new(RgbMode::Breathe, Rgb::new(0x12, 0x34, 0x56), RgbSpeed::High);
+
+    assert_eq!(effect.to_raw(false), (0x3412_01b3, 0xf556));
+    assert_eq!(effect.to_attribute(true), "1 1 18 52 86 2");
+    assert_eq!(
+        RgbEffect::from_attribute(&effect.to_attribute(true)),
+        Some(effect)
+    );
+
+    let flags = RgbPowerFlags {
+        sleep: false,
+        ..Default::default()
+    };
+    assert_eq!(flags.to_raw(false), 0x8a_00bd);
+}
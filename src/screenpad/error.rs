@@ -0,0 +1,29 @@
+//! Error types and messages for the screenpad module.
+
+use crate::{debugfs::error::HardwareError, persist::PersistError};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScreenpadError {
+    #[error("ScreenPad is not present on this machine!")]
+    NotFound,
+
+    #[error("No main panel backlight found under `/sys/class/backlight`!")]
+    MainBacklightNotFound,
+
+    #[error("Failed to read `{path:?}`! {error}")]
+    ReadFailed {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+
+    #[error("Unexpected value `{value}` in `{path:?}`!")]
+    UnexpectedValue { path: PathBuf, value: String },
+
+    #[error("{0}")]
+    PersistError(#[from] PersistError),
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
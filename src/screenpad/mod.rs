@@ -0,0 +1,244 @@
+//! ASUS_WMI_DEVID_SCREENPAD_POWER and ASUS_WMI_DEVID_SCREENPAD_LIGHT
+//!
+//! Secondary display of the Zenbook Pro Duo and ScreenPad models. The last non-zero
+//! brightness is stored under [STATE_DIR](crate::persist::STATE_DIR), so turning the
+//! panel on restores it. The brightness can also follow the main panel's backlight.
+
+pub mod error;
+
+use crate::{
+    auto_impl_config,
+    debugfs::{Config, Hardware},
+    error::StateError,
+    persist::Persistent,
+    sysfs,
+    worker::Worker,
+};
+use error::ScreenpadError;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub const POWER_DEV_ID: u64 = 0x00050031;
+pub const LIGHT_DEV_ID: u64 = 0x00050032;
+
+const BACKLIGHT_PATH: &str = "/sys/class/backlight";
+/// Backlight device registered by asus-wmi for the ScreenPad itself.
+const SCREENPAD_BACKLIGHT: &str = "asus_screenpad";
+/// Backlight `type`s in order of preference.
+const BACKLIGHT_TYPES: [&str; 3] = ["firmware", "platform", "raw"];
+
+auto_impl_config!(ScreenpadPower, u8, Off = 0, On = 1);
+
+pub const fn power() -> Hardware<ScreenpadPower> {
+    Hardware::new(POWER_DEV_ID)
+}
+
+pub const fn light() -> Hardware<u8> {
+    Hardware::new(LIGHT_DEV_ID)
+}
+
+/// ScreenPad power and brightness.
+#[derive(Debug, Clone)]
+pub struct Screenpad {
+    power: Hardware<ScreenpadPower>,
+    light: Hardware<u8>,
+    last_brightness: Persistent<u8>,
+}
+
+impl Screenpad {
+    /// Lowest brightness the panel is set to when turned on, as used by asus-wmi.
+    pub const MIN_BRIGHTNESS: u8 = 20;
+    /// Brightness used when turning on without a stored brightness.
+    pub const DEFAULT_BRIGHTNESS: u8 = 60;
+
+    pub fn new() -> Self {
+        Screenpad {
+            power: power(),
+            light: light(),
+            last_brightness: Persistent::new("screenpad_brightness", light()),
+        }
+    }
+
+    /// Check the ScreenPad is present on the machine.
+    pub fn detect() -> Result<Self, ScreenpadError> {
+        let screenpad = Self::new();
+        if screenpad.power.is_present()? {
+            Ok(screenpad)
+        } else {
+            Err(ScreenpadError::NotFound)
+        }
+    }
+
+    pub fn is_on(&self) -> Result<bool, ScreenpadError> {
+        Ok(self.power.read_masked(0x1)? == ScreenpadPower::On)
+    }
+
+    /// Read the current brightness, `0` to `255`.
+    pub fn brightness(&self) -> Result<u8, ScreenpadError> {
+        Ok(self.light.read_masked(0xFF)?)
+    }
+
+    /// Set the brightness, remembering it when non-zero. `0` turns the panel off.
+    pub fn set_brightness(&self, brightness: u8) -> Result<(), ScreenpadError> {
+        if brightness == 0 {
+            return self.off();
+        }
+
+        if !self.is_on()? {
+            self.power.apply(ScreenpadPower::On)?;
+        }
+        self.last_brightness.apply(brightness)?;
+        Ok(())
+    }
+
+    /// Turn the panel on, restoring the last non-zero brightness.
+    pub fn on(&self) -> Result<(), ScreenpadError> {
+        let brightness = self
+            .last_brightness
+            .load()?
+            .unwrap_or(Self::DEFAULT_BRIGHTNESS)
+            .max(Self::MIN_BRIGHTNESS);
+
+        self.power.apply(ScreenpadPower::On)?;
+        self.light.apply(brightness)?;
+        Ok(())
+    }
+
+    /// Turn the panel off, remembering the current brightness.
+    pub fn off(&self) -> Result<(), ScreenpadError> {
+        if self.is_on()? {
+            let brightness = self.brightness()?;
+            if brightness > 0 {
+                self.last_brightness.save(brightness)?;
+            }
+        }
+
+        self.power.apply(ScreenpadPower::Off)?;
+        Ok(())
+    }
+
+    /// Switch the panel on or off, returning whether it is now on.
+    pub fn toggle(&self) -> Result<bool, ScreenpadError> {
+        if self.is_on()? {
+            self.off()?;
+            Ok(false)
+        } else {
+            self.on()?;
+            Ok(true)
+        }
+    }
+
+    /// Set the brightness to `percent` of the main panel's relative brightness,
+    /// returning the brightness applied.
+    pub fn sync_to_main(&self, percent: u8) -> Result<u8, ScreenpadError> {
+        let brightness = screenpad_brightness(MainBacklight::find()?.fraction()?, percent);
+        if self.is_on()? {
+            self.set_brightness(brightness)?;
+        }
+        Ok(brightness)
+    }
+
+    /// Keep syncing to the main panel every `interval` on a background thread.
+    pub fn follow_main(
+        &self,
+        percent: u8,
+        interval: Duration,
+    ) -> Worker<Result<(), ScreenpadError>> {
+        let screenpad = self.clone();
+        Worker::spawn(move |signal| {
+            let main = MainBacklight::find()?;
+            let mut last = None;
+            loop {
+                let fraction = main.fraction()?;
+                if last != Some(fraction) && screenpad.is_on()? {
+                    screenpad.set_brightness(screenpad_brightness(fraction, percent))?;
+                    last = Some(fraction);
+                }
+                if signal.sleep(interval) {
+                    return Ok(());
+                }
+            }
+        })
+    }
+}
+
+impl Default for Screenpad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ScreenPad brightness for `percent` of the main panel's relative brightness.
+fn screenpad_brightness(fraction: f32, percent: u8) -> u8 {
+    let brightness = fraction * f32::from(percent.min(100)) / 100.0 * f32::from(u8::MAX);
+    (brightness.round() as u8).max(Screenpad::MIN_BRIGHTNESS)
+}
+
+/// Backlight of the main panel under `/sys/class/backlight`.
+#[derive(Debug, Clone)]
+pub struct MainBacklight {
+    path: PathBuf,
+}
+
+impl MainBacklight {
+    /// Backlight which is not the ScreenPad, preferring `firmware` over `platform`
+    /// over `raw` by its `type`, like the desktop environments do.
+    pub fn find() -> Result<Self, ScreenpadError> {
+        let entries = fs::read_dir(BACKLIGHT_PATH).map_err(|error| ScreenpadError::ReadFailed {
+            path: BACKLIGHT_PATH.into(),
+            error,
+        })?;
+
+        let mut backlights: Vec<(usize, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name() != SCREENPAD_BACKLIGHT)
+            .filter_map(|entry| {
+                let kind = sysfs::read_trimmed(entry.path().join("type")).ok()?;
+                let rank = BACKLIGHT_TYPES.iter().position(|t| *t == kind)?;
+                Some((rank, entry.path()))
+            })
+            .collect();
+        backlights.sort();
+
+        backlights
+            .into_iter()
+            .next()
+            .map(|(_, path)| MainBacklight { path })
+            .ok_or(ScreenpadError::MainBacklightNotFound)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self, name: &str) -> Result<u32, ScreenpadError> {
+        let path = self.path.join(name);
+        let value = sysfs::read_trimmed(&path).map_err(|error| ScreenpadError::ReadFailed {
+            path: path.clone(),
+            error,
+        })?;
+        value
+            .parse()
+            .map_err(|_| ScreenpadError::UnexpectedValue { path, value })
+    }
+
+    /// Current brightness relative to the maximum, from `0.0` to `1.0`.
+    pub fn fraction(&self) -> Result<f32, ScreenpadError> {
+        let max = self.read("max_brightness")?;
+        if max == 0 {
+            return Ok(0.0);
+        }
+        Ok(self.read("brightness")? as f32 / max as f32)
+    }
+}
+
+#[test]
+fn brightness_from_main_panel() {
+    assert_eq!(screenpad_brightness(1.0, 100), 255);
+    assert_eq!(screenpad_brightness(0.5, 100), 128);
+    assert_eq!(screenpad_brightness(1.0, 50), 128);
+    assert_eq!(screenpad_brightness(0.0, 100), Screenpad::MIN_BRIGHTNESS);
+}
@@ -0,0 +1,22 @@
+//! Error types and messages for the sensors module.
+
+use crate::debugfs::error::HardwareError;
+#[cfg(feature = "pwm")]
+use crate::pwm::pwm_enable::error::InputReadError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SensorError {
+    #[error("Sensor with DEV_ID `{dev_id:#010x}` is not present on this machine!")]
+    NotPresent { dev_id: u64 },
+
+    #[error("Sensor with DEV_ID `{dev_id:#010x}` reported an invalid value `{value:#x}`!")]
+    InvalidValue { dev_id: u64, value: u64 },
+
+    #[cfg(feature = "pwm")]
+    #[error("{0}")]
+    InputReadError(#[from] InputReadError),
+
+    #[error("{0}")]
+    HardwareError(#[from] HardwareError),
+}
@@ -0,0 +1,115 @@
+//! ASUS_WMI_DEVID_CPU_FAN_CTRL, ASUS_WMI_DEVID_GPU_FAN_CTRL,
+//! ASUS_WMI_DEVID_MID_FAN_CTRL and ASUS_WMI_DEVID_THERMAL_CTRL
+//!
+//! Read-only fan speed and temperature, decoded from the DSTS value the same way the
+//! [asus-wmi driver](https://github.com/torvalds/linux/blob/master/drivers/platform/x86/asus-wmi.c)
+//! does for its hwmon device. Only reads are issued, nothing is written to the firmware.
+
+pub mod error;
+
+use crate::debugfs::{Hardware, DSTS_PRESENCE_BIT};
+#[cfg(feature = "pwm")]
+use crate::pwm::pwm_enable::traits::ReadConfig;
+use error::SensorError;
+
+pub const CPU_FAN_DEV_ID: u64 = 0x00110013;
+pub const GPU_FAN_DEV_ID: u64 = 0x00110014;
+pub const MID_FAN_DEV_ID: u64 = 0x00110031;
+pub const THERMAL_DEV_ID: u64 = 0x00110011;
+
+/// Bits of the DSTS value holding the reading.
+const VALUE_MASK: u64 = 0xFFFF;
+
+/// Read the DSTS value of `dev_id`, checking the presence bit.
+fn read_value(dev_id: u64) -> Result<u64, SensorError> {
+    let dsts = Hardware::<u64>::new(dev_id).read_dsts()?;
+    if dsts & DSTS_PRESENCE_BIT == 0 {
+        return Err(SensorError::NotPresent { dev_id });
+    }
+    Ok(dsts & VALUE_MASK)
+}
+
+/// The firmware reports the speed in hundreds of RPM.
+const fn decode_rpm(value: u64) -> u32 {
+    value as u32 * 100
+}
+
+/// The firmware reports the temperature in deci-Kelvin.
+fn decode_celsius(value: u64) -> f32 {
+    (value as f32 - 2731.5) / 10.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fan {
+    Cpu,
+    Gpu,
+    Mid,
+}
+
+impl Fan {
+    pub const ALL: [Fan; 3] = [Fan::Cpu, Fan::Gpu, Fan::Mid];
+
+    pub const fn dev_id(self) -> u64 {
+        match self {
+            Fan::Cpu => CPU_FAN_DEV_ID,
+            Fan::Gpu => GPU_FAN_DEV_ID,
+            Fan::Mid => MID_FAN_DEV_ID,
+        }
+    }
+
+    /// Label of the fan in the asus hwmon device, as given by
+    /// `ReadConfig::get_label` with the `pwm` feature.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Fan::Cpu => "cpu_fan",
+            Fan::Gpu => "gpu_fan",
+            Fan::Mid => "mid_fan",
+        }
+    }
+
+    /// Fans present on this machine.
+    pub fn available() -> Result<Vec<Fan>, SensorError> {
+        let mut fans = Vec::new();
+        for fan in Self::ALL {
+            if Hardware::<u64>::new(fan.dev_id()).is_present()? {
+                fans.push(fan);
+            }
+        }
+        Ok(fans)
+    }
+
+    /// Current speed of the fan in RPM, `0` when stopped.
+    pub fn rpm(self) -> Result<u32, SensorError> {
+        read_value(self.dev_id()).map(decode_rpm)
+    }
+
+    /// Read the speed through both WMI and hwmon, to check they agree.
+    ///
+    /// Returns the RPM from WMI and from the given pwm device respectively.
+    #[cfg(feature = "pwm")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "pwm")))]
+    pub fn cross_check(self, pwm: &impl ReadConfig) -> Result<(u32, u32), SensorError> {
+        Ok((self.rpm()?, u32::from(pwm.get_input()?)))
+    }
+}
+
+/// Current temperature of the thermal sensor in °C.
+pub fn temperature() -> Result<f32, SensorError> {
+    let value = read_value(THERMAL_DEV_ID)?;
+    // asus-wmi hides the sensor when it reports (near) absolute zero
+    if value <= 1 {
+        return Err(SensorError::InvalidValue {
+            dev_id: THERMAL_DEV_ID,
+            value,
+        });
+    }
+    Ok(decode_celsius(value))
+}
+
+#[test]
+fn decode_readings() {
+    assert_eq!(decode_rpm(0), 0);
+    assert_eq!(decode_rpm(0x2A), 4200);
+    assert_eq!(decode_celsius(2731), -0.05);
+    assert_eq!(decode_celsius(3181), 44.95);
+}
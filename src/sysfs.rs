@@ -0,0 +1,17 @@
+//! Small helpers to read and write sysfs/procfs attribute files.
+
+use std::{fs, io, path::Path};
+
+/// Read the attribute file, stripping the trailing newline.
+pub(crate) fn read_trimmed(path: impl AsRef<Path>) -> io::Result<String> {
+    let mut value = fs::read_to_string(path)?;
+    while value.ends_with('\n') {
+        value.pop();
+    }
+    Ok(value)
+}
+
+/// Write the value to the attribute file.
+pub(crate) fn write(path: impl AsRef<Path>, value: impl ToString) -> io::Result<()> {
+    fs::write(path, value.to_string())
+}
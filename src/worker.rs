@@ -0,0 +1,109 @@
+//! Cancellable background threads, used by the watchers and effects of this crate.
+
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Tells a background thread when it has been asked to stop.
+#[derive(Debug, Clone, Default)]
+pub struct StopSignal {
+    inner: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl StopSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the thread to stop.
+    pub fn stop(&self) {
+        let (stopped, condvar) = &*self.inner;
+        *stopped.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        condvar.notify_all();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        *self.inner.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Sleep for the given duration, waking up early if asked to stop.
+    ///
+    /// Returns `true` if the thread has been asked to stop.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let (stopped, condvar) = &*self.inner;
+        let guard = stopped.lock().unwrap_or_else(|e| e.into_inner());
+        let (guard, _) = condvar
+            .wait_timeout_while(guard, duration, |stopped| !*stopped)
+            .unwrap_or_else(|e| e.into_inner());
+        *guard
+    }
+}
+
+/// Handle of a background thread, which is stopped and joined when dropped.
+#[derive(Debug)]
+pub struct Worker<T> {
+    signal: StopSignal,
+    handle: Option<JoinHandle<T>>,
+}
+
+impl<T: Send + 'static> Worker<T> {
+    /// Spawn the thread, passing it the signal to watch for.
+    pub(crate) fn spawn(work: impl FnOnce(StopSignal) -> T + Send + 'static) -> Self {
+        let signal = StopSignal::new();
+        let thread_signal = signal.clone();
+        Worker {
+            signal,
+            handle: Some(thread::spawn(move || work(thread_signal))),
+        }
+    }
+}
+
+impl<T> Worker<T> {
+    /// Whether the thread has finished on its own (or panicked).
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Ask the thread to stop and wait for its result.
+    ///
+    /// Returns `None` if the thread panicked.
+    pub fn stop(mut self) -> Option<T> {
+        self.signal.stop();
+        self.handle.take().and_then(|handle| handle.join().ok())
+    }
+
+    /// Wait for the thread to finish on its own.
+    ///
+    /// Returns `None` if the thread panicked.
+    pub fn join(mut self) -> Option<T> {
+        self.handle.take().and_then(|handle| handle.join().ok())
+    }
+}
+
+impl<T> Drop for Worker<T> {
+    fn drop(&mut self) {
+        self.signal.stop();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[test]
+fn worker_stops_promptly() {
+    use std::time::Instant;
+
+    let worker = Worker::spawn(|signal| {
+        let mut ticks = 0;
+        while !signal.sleep(Duration::from_secs(60)) {
+            ticks += 1;
+        }
+        ticks
+    });
+
+    let start = Instant::now();
+    assert_eq!(worker.stop(), Some(0));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}